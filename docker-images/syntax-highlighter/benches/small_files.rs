@@ -0,0 +1,135 @@
+//! Compares fresh tree-sitter parsers and highlighters per file with the pooled ones
+//! from `scip_treesitter_languages::pool`, on a lot of small files.
+//!
+//! Run with `cargo bench --bench small_files`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use scip_treesitter_languages::{
+    highlights::get_highlighting_configuration, parsers::BundledParser, pool,
+};
+use tree_sitter::Parser;
+use tree_sitter_highlight::Highlighter;
+
+const SOURCES: &[(&str, &str)] = &[
+    (
+        "go",
+        include_str!("../crates/sg-syntax/src/snapshots/files/golang.go"),
+    ),
+    (
+        "python",
+        include_str!("../crates/sg-syntax/src/snapshots/files/python.py"),
+    ),
+    (
+        "rust",
+        include_str!("../crates/sg-syntax/src/snapshots/files/rust.rs"),
+    ),
+    (
+        "typescript",
+        include_str!("../crates/sg-syntax/src/snapshots/files/typescript.ts"),
+    ),
+];
+
+// Every paragraph of the snapshot files is a file of its own, a few lines each. Most of
+// them don't parse cleanly, which doesn't matter for the overhead we're measuring.
+fn small_files() -> Vec<(BundledParser, &'static str)> {
+    SOURCES
+        .iter()
+        .flat_map(|(language, source)| {
+            let parser = BundledParser::get_parser(language).expect("bundled language");
+            source
+                .split("\n\n")
+                .filter(|file| !file.trim().is_empty())
+                .map(move |file| (parser, file))
+        })
+        .collect()
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let files = small_files();
+    let mut group = c.benchmark_group("small_files/parse");
+    group.throughput(Throughput::Elements(files.len() as u64));
+
+    group.bench_function("fresh", |b| {
+        b.iter(|| {
+            for (language, file) in &files {
+                let mut parser = Parser::new();
+                parser.set_language(language.get_language()).unwrap();
+                black_box(parser.parse(file, None));
+            }
+        })
+    });
+
+    group.bench_function("pooled", |b| {
+        b.iter(|| {
+            for (language, file) in &files {
+                black_box(pool::parser(*language).parse(file, None));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn highlight(highlighter: &mut Highlighter, language: &BundledParser, file: &str) {
+    let config = get_highlighting_configuration(language.get_language_name()).unwrap();
+    let events = highlighter
+        .highlight(config, file.as_bytes(), None, |l| {
+            get_highlighting_configuration(l)
+        })
+        .unwrap();
+    black_box(events.count());
+}
+
+fn bench_highlight(c: &mut Criterion) {
+    let files = small_files();
+    let mut group = c.benchmark_group("small_files/highlight");
+    group.throughput(Throughput::Elements(files.len() as u64));
+
+    group.bench_function("fresh", |b| {
+        b.iter(|| {
+            for (language, file) in &files {
+                highlight(&mut Highlighter::new(), language, file);
+            }
+        })
+    });
+
+    group.bench_function("pooled", |b| {
+        b.iter(|| {
+            for (language, file) in &files {
+                highlight(&mut pool::highlighter(), language, file);
+            }
+        })
+    });
+
+    group.finish();
+}
+
+// What the server does for every file: highlights, locals and symbols. Only uses the
+// pools, to track the per-file overhead that is left.
+fn bench_index(c: &mut Criterion) {
+    let files = small_files();
+    let mut group = c.benchmark_group("small_files/index");
+    group.throughput(Throughput::Elements(files.len() as u64));
+
+    group.bench_function("highlight_with_locals", |b| {
+        b.iter(|| {
+            for (language, file) in &files {
+                let filetype = language.get_language_name();
+                black_box(sg_syntax::treesitter_index(filetype, file, true, false).ok());
+            }
+        })
+    });
+
+    group.bench_function("symbols", |b| {
+        b.iter(|| {
+            for (language, file) in &files {
+                black_box(scip_syntax::get_symbols(*language, file.as_bytes()).ok());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_highlight, bench_index);
+criterion_main!(benches);
@@ -0,0 +1,127 @@
+//! Compares one shared syntect SyntaxSet with the per-thread copies the server used to keep.
+//!
+//! Run with `cargo bench --bench syntax_set`. Memory usage is reported before the timings.
+
+use std::{
+    sync::{Arc, Barrier},
+    thread,
+    time::Instant,
+};
+
+use criterion::{black_box, criterion_group, Criterion};
+use sg_syntax::{syntaxes::syntax_set, DocumentGenerator};
+use syntect::parsing::SyntaxSet;
+
+// Roughly the number of rocket worker threads on a small machine
+const WORKERS: usize = 8;
+
+const SOURCE: &str = include_str!("../crates/sg-syntax/src/snapshots/files/golang.go");
+
+fn highlight(syntax_set: &SyntaxSet) {
+    let syntax = syntax_set
+        .find_syntax_by_extension("go")
+        .expect("go syntax");
+    black_box(DocumentGenerator::new(syntax_set, syntax, SOURCE, None).generate());
+}
+
+fn resident_set_size() -> Option<usize> {
+    // The second field is the resident set size in pages. Assumes 4KiB pages, which is
+    // fine for comparing the two setups with each other.
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: usize = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
+
+fn mebibytes(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn report_memory() {
+    let Some(baseline) = resident_set_size() else {
+        eprintln!("syntax_set/rss: /proc/self/statm is not available, skipping");
+        return;
+    };
+
+    // Shared: a single set, used by every worker.
+    let start = Instant::now();
+    syntax_set();
+    println!(
+        "syntax_set/startup: shared set loaded in {:?}",
+        start.elapsed()
+    );
+
+    let handles: Vec<_> = (0..WORKERS)
+        .map(|_| thread::spawn(|| highlight(syntax_set())))
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let shared = resident_set_size().unwrap_or(baseline);
+    println!(
+        "syntax_set/rss: shared by {WORKERS} workers: +{:.1} MiB",
+        mebibytes(shared.saturating_sub(baseline))
+    );
+
+    // Thread local: every worker loads (and compiles the regexes of) its own copy.
+    // The copies are kept alive until we measured, like they would be in the server.
+    let barrier = Arc::new(Barrier::new(WORKERS + 1));
+    let handles: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                let syntax_set = SyntaxSet::load_defaults_newlines();
+                highlight(&syntax_set);
+                barrier.wait();
+                barrier.wait();
+            })
+        })
+        .collect();
+
+    barrier.wait();
+    let copies = resident_set_size().unwrap_or(shared);
+    barrier.wait();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    println!(
+        "syntax_set/rss: one copy per worker for {WORKERS} workers: +{:.1} MiB",
+        mebibytes(copies.saturating_sub(shared))
+    );
+}
+
+fn bench_first_request(c: &mut Criterion) {
+    let mut group = c.benchmark_group("syntax_set");
+    group.sample_size(10);
+
+    group.bench_function("load_defaults", |b| {
+        b.iter(SyntaxSet::load_defaults_newlines)
+    });
+
+    // The first request on a fresh worker thread, which is what every worker used to pay
+    // for with a thread local set.
+    group.bench_function("first_request_thread_local", |b| {
+        b.iter(|| {
+            thread::spawn(|| highlight(&SyntaxSet::load_defaults_newlines()))
+                .join()
+                .unwrap()
+        })
+    });
+
+    group.bench_function("first_request_shared", |b| {
+        syntax_set();
+        b.iter(|| thread::spawn(|| highlight(syntax_set())).join().unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_first_request);
+
+fn main() {
+    report_memory();
+
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
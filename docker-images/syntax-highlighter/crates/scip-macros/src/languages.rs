@@ -0,0 +1,341 @@
+//! The table of bundled languages, see [`crate::bundled_languages`].
+
+use std::path::Path;
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    braced, bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    token::Comma,
+    Ident, LitStr, Path as SynPath, Result, Token,
+};
+
+// The query every bundled language has, in the queries folder of the crate that
+// expands the table
+const REQUIRED_QUERY: &str = "highlights";
+
+// Queries that are left empty when a language doesn't have them
+const OPTIONAL_QUERIES: &[&str] = &["injections", "locals"];
+
+// Per-language SyntaxKind overlays, see scip_treesitter_languages::highlights
+const SYNTAX_KINDS_FILE: &str = "syntax-kinds.txt";
+
+struct Language {
+    variant: Ident,
+    name: String,
+    language: SynPath,
+    extensions: Vec<LitStr>,
+    // Whole file names, for files that don't have an extension or whose extension is
+    // shared with other files, e.g. Dockerfile and CMakeLists.txt
+    filenames: Vec<LitStr>,
+    // Other names of the language, e.g. the ones go-enry uses
+    aliases: Vec<LitStr>,
+}
+
+impl Parse for Language {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let variant: Ident = input.parse()?;
+        let content;
+        braced!(content in input);
+
+        let mut language = None;
+        let mut extensions = vec![];
+        let mut filenames = vec![];
+        let mut aliases = vec![];
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            content.parse::<Token![:]>()?;
+
+            match key.to_string().as_str() {
+                "language" => language = Some(content.parse()?),
+                "extensions" => extensions = parse_list(&content)?,
+                "filenames" => filenames = parse_list(&content)?,
+                "aliases" => aliases = parse_list(&content)?,
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!(
+                            "unknown key {key}, expected language, extensions, filenames or aliases"
+                        ),
+                    ))
+                }
+            }
+
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+
+        let language = language
+            .ok_or_else(|| syn::Error::new(variant.span(), "missing language: <constructor>"))?;
+
+        Ok(Language {
+            // Like include_scip_query!, which also lowercases identifiers
+            name: variant.to_string().to_lowercase(),
+            variant,
+            language,
+            extensions,
+            filenames,
+            aliases,
+        })
+    }
+}
+
+fn parse_list(input: ParseStream) -> Result<Vec<LitStr>> {
+    let list;
+    bracketed!(list in input);
+    Ok(Punctuated::<LitStr, Comma>::parse_terminated(&list)?
+        .into_iter()
+        .collect())
+}
+
+pub(crate) struct LanguageTable {
+    languages: Vec<Language>,
+}
+
+impl Parse for LanguageTable {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let languages = Punctuated::<Language, Comma>::parse_terminated(input)?
+            .into_iter()
+            .collect();
+
+        Ok(LanguageTable { languages })
+    }
+}
+
+impl LanguageTable {
+    pub(crate) fn check(&self, queries: &Path) -> Result<()> {
+        let mut extensions: Vec<String> = vec![];
+        let mut filenames: Vec<String> = vec![];
+        let mut names: Vec<String> = self.languages.iter().map(|l| l.name.clone()).collect();
+        for language in &self.languages {
+            let path = queries
+                .join(&language.name)
+                .join(format!("{REQUIRED_QUERY}.scm"));
+            if !path.exists() {
+                return Err(syn::Error::new(
+                    language.variant.span(),
+                    format!("{} is missing", path.display()),
+                ));
+            }
+
+            for extension in &language.extensions {
+                if extensions.contains(&extension.value()) {
+                    return Err(syn::Error::new(
+                        extension.span(),
+                        format!("extension {:?} is used twice", extension.value()),
+                    ));
+                }
+                extensions.push(extension.value());
+            }
+
+            for filename in &language.filenames {
+                if filenames.contains(&filename.value()) {
+                    return Err(syn::Error::new(
+                        filename.span(),
+                        format!("file name {:?} is used twice", filename.value()),
+                    ));
+                }
+                filenames.push(filename.value());
+            }
+
+            for alias in &language.aliases {
+                if names.contains(&alias.value()) {
+                    return Err(syn::Error::new(
+                        alias.span(),
+                        format!("{:?} is already the name of a language", alias.value()),
+                    ));
+                }
+                names.push(alias.value());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn expand(&self, queries: &Path) -> TokenStream {
+        let variants: Vec<_> = self.languages.iter().map(|l| &l.variant).collect();
+        let names: Vec<_> = self.languages.iter().map(|l| &l.name).collect();
+        let constructors: Vec<_> = self.languages.iter().map(|l| &l.language).collect();
+        let extensions: Vec<_> = self.languages.iter().map(|l| &l.extensions).collect();
+
+        // Languages without extensions can't be in the extension match, an empty
+        // pattern isn't valid
+        let with_extensions: Vec<_> = self
+            .languages
+            .iter()
+            .filter(|l| !l.extensions.is_empty())
+            .collect();
+        let extension_variants = with_extensions.iter().map(|l| &l.variant);
+        let extension_patterns = with_extensions.iter().map(|l| &l.extensions);
+
+        let with_filenames: Vec<_> = self
+            .languages
+            .iter()
+            .filter(|l| !l.filenames.is_empty())
+            .collect();
+        let filename_variants = with_filenames.iter().map(|l| &l.variant);
+        let filename_patterns = with_filenames.iter().map(|l| &l.filenames);
+
+        let aliases = self.languages.iter().map(|l| &l.aliases);
+
+        // Queries that exist can inherit from other languages, so only missing ones go
+        // through include_project_file_optional!
+        let required_query = REQUIRED_QUERY;
+        let optional_queries = self.languages.iter().map(|language| {
+            let name = &language.name;
+            OPTIONAL_QUERIES.iter().map(move |query| {
+                if queries.join(name).join(format!("{query}.scm")).exists() {
+                    quote! { ::scip_macros::include_scip_query!(#name, #query) }
+                } else {
+                    let file = format!("/{query}.scm");
+                    quote! { ::scip_macros::include_project_file_optional!("queries/", #name, #file) }
+                }
+            })
+            .collect::<Vec<_>>()
+        });
+
+        // Only languages that have the file get an overlay
+        let overlays = self.languages.iter().filter_map(|language| {
+            let path = queries.join(&language.name).join(SYNTAX_KINDS_FILE);
+            if !path.exists() {
+                return None;
+            }
+
+            let variant = &language.variant;
+            let path = path.to_str().expect("query paths must be valid UTF-8");
+            Some(quote! { (BundledParser::#variant, include_str!(#path)) })
+        });
+
+        let expanded = quote! {
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[allow(non_camel_case_types)]
+            pub enum BundledParser {
+                #( #variants, )*
+
+                // A grammar loaded at runtime, see crate::plugins. The id is its index in
+                // crate::plugins::plugins().
+                Plugin(usize),
+            }
+
+            /// Every bundled language, including those that weren't compiled in.
+            pub const ALL_BUNDLED: &[BundledParser] = &[ #( BundledParser::#variants, )* ];
+
+            impl BundledParser {
+                pub fn get_language(self) -> ::tree_sitter::Language {
+                    match self {
+                        #(
+                            #[cfg(feature = #names)]
+                            BundledParser::#variants => #constructors(),
+                        )*
+                        BundledParser::Plugin(id) => crate::plugins::get_plugin(id)
+                            .expect("plugin is installed")
+                            .language,
+                        // Only reachable when a grammar is disabled, get_parser never
+                        // returns those
+                        #[allow(unreachable_patterns)]
+                        _ => panic!("{:?} was not compiled in", self),
+                    }
+                }
+
+                /// Whether the grammar was compiled in, see the features in Cargo.toml.
+                /// Disabled languages are not returned by any lookup, as if we didn't
+                /// know about them.
+                pub fn is_enabled(self) -> bool {
+                    match self {
+                        #( BundledParser::#variants => cfg!(feature = #names), )*
+                        BundledParser::Plugin(_) => true,
+                    }
+                }
+
+                pub fn get_parser(name: &str) -> Option<Self> {
+                    let parser = match name {
+                        #( #names #( | #aliases )* => Some(BundledParser::#variants), )*
+                        _ => None,
+                    };
+
+                    // A plugin can provide a language that wasn't compiled in
+                    parser
+                        .filter(|parser| parser.is_enabled())
+                        .or_else(|| crate::plugins::find_plugin(name).map(BundledParser::Plugin))
+                }
+
+                pub fn get_language_name(&self) -> &str {
+                    match self {
+                        #( BundledParser::#variants => #names, )*
+                        BundledParser::Plugin(id) => crate::plugins::get_plugin(*id)
+                            .expect("plugin is installed")
+                            .name,
+                    }
+                }
+
+                pub fn get_language_extensions(&self) -> ::std::collections::HashSet<&str> {
+                    let extensions: &[&str] = match self {
+                        #( BundledParser::#variants => &[ #( #extensions ),* ], )*
+                        BundledParser::Plugin(_) => return [self.get_language_name()].into(),
+                    };
+
+                    extensions.iter().copied().collect()
+                }
+
+                pub fn get_parser_from_extension(extension: &str) -> Option<Self> {
+                    let parser = match extension {
+                        #( #( #extension_patterns )|* => Some(BundledParser::#extension_variants), )*
+                        _ => None,
+                    };
+
+                    // Plugins don't know about extensions, so the language name has to do
+                    parser
+                        .filter(|parser| parser.is_enabled())
+                        .or_else(|| crate::plugins::find_plugin(extension).map(BundledParser::Plugin))
+                }
+
+                /// The language of a file by its whole name, e.g. `Dockerfile`, and
+                /// otherwise by its extension.
+                pub fn get_parser_from_file_name(file_name: &str) -> Option<Self> {
+                    let parser = match file_name {
+                        #( #( #filename_patterns )|* => Some(BundledParser::#filename_variants), )*
+                        _ => None,
+                    };
+
+                    parser.filter(|parser| parser.is_enabled()).or_else(|| {
+                        let (_, extension) = file_name.rsplit_once('.')?;
+                        BundledParser::get_parser_from_extension(extension)
+                    })
+                }
+
+                /// The highlights, injections and locals queries, for languages that were
+                /// compiled in.
+                pub(crate) fn highlight_queries(self) -> Option<(&'static str, &'static str, &'static str)> {
+                    match self {
+                        #(
+                            #[cfg(feature = #names)]
+                            BundledParser::#variants => Some((
+                                ::scip_macros::include_scip_query!(#names, #required_query),
+                                #( #optional_queries, )*
+                            )),
+                        )*
+                        _ => None,
+                    }
+                }
+            }
+
+            /// The `syntax-kinds.txt` of every bundled language that has one, in the
+            /// order of the table.
+            pub(crate) const SYNTAX_KIND_OVERLAYS: &[(BundledParser, &str)] = &[ #( #overlays, )* ];
+
+            /// Calls `$callback!` with a `(Variant, "name")` row for every bundled
+            /// language, including those that weren't compiled in.
+            #[macro_export]
+            macro_rules! with_bundled_languages {
+                ($callback:ident) => {
+                    $callback! { #( (#variants, #names) ),* }
+                };
+            }
+        };
+
+        expanded.into()
+    }
+}
@@ -9,9 +9,12 @@ use syn::{
     Ident, LitStr, Result, Token,
 };
 
+mod languages;
+
 struct ScipQuery {
     pub lang: String,
     pub query: String,
+    pub query_lit: LitStr,
 }
 impl Parse for ScipQuery {
     fn parse(input: ParseStream) -> Result<Self> {
@@ -29,6 +32,7 @@ impl Parse for ScipQuery {
         Ok(Self {
             lang,
             query: query.value(),
+            query_lit: query,
         })
     }
 }
@@ -36,14 +40,149 @@ impl Parse for ScipQuery {
 /// Use to get a particular query from the scip-semantic repo.
 ///     Will do this at compile time and directly include
 ///
+/// `; inherits: <lang>,...` lines are replaced with the same query of those languages,
+/// see scip_treesitter_languages::inherits for the rules. That crate depends on this
+/// one, so they are implemented again here, reporting problems as compile errors.
+///
 /// Example:
 /// > include_scip_query!("rust", "scip-tags");
 #[proc_macro]
 pub fn include_scip_query(input: TokenStream) -> TokenStream {
-    let ScipQuery { lang, query } = parse_macro_input!(input as ScipQuery);
-    // let base = std::env::var("CARGO_MANIFEST_DIR").unwrap() + "/";
+    let ScipQuery {
+        lang,
+        query,
+        query_lit,
+    } = parse_macro_input!(input as ScipQuery);
+
+    let base: PathBuf = std::env::var("CARGO_MANIFEST_DIR")
+        .expect("CARGO_MANIFEST_DIR must be set")
+        .into();
+    let mut resolver = QueryResolver {
+        dir: base.join("queries"),
+        query,
+        stack: vec![],
+        files: vec![],
+    };
+
+    let text = match resolver.resolve(&lang) {
+        Ok(text) => text,
+        Err(err) => {
+            return syn::Error::new(query_lit.span(), err)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    // The text is inlined, include the files anyway so changing them rebuilds the crate
+    let files = resolver
+        .files
+        .iter()
+        .map(|file| file.to_str().expect("query paths must be valid UTF-8"));
+
+    quote! {{
+        #( const _: &str = include_str!(#files); )*
+        #text
+    }}
+    .into()
+}
+
+/// Generates `BundledParser` and its lookups from one row per bundled language:
+///
+/// ```ignore
+/// bundled_languages! {
+///     Go {
+///         language: tree_sitter_go::language,
+///         extensions: ["go"],
+///     },
+/// }
+/// ```
+///
+/// `filenames` and `aliases` are optional. Files are detected by their whole name
+/// before their extension, see `BundledParser::get_parser_from_file_name`, and
+/// `BundledParser::get_parser` also knows a language by its aliases.
+///
+/// The name of a language is its lowercased variant, which is also the name of its
+/// cargo feature and of its folder in `queries/`. That folder must have a highlights
+/// query, injections and locals are optional, and so is a `syntax-kinds.txt`, which is
+/// added to `SYNTAX_KIND_OVERLAYS`. `with_bundled_languages!` passes the rows on to
+/// other crates, e.g. scip-syntax picks up the queries it has for them.
+#[proc_macro]
+pub fn bundled_languages(input: TokenStream) -> TokenStream {
+    let table = parse_macro_input!(input as languages::LanguageTable);
+
+    let base: PathBuf = std::env::var("CARGO_MANIFEST_DIR")
+        .expect("CARGO_MANIFEST_DIR must be set")
+        .into();
+    let queries = base.join("queries");
+    if let Err(err) = table.check(&queries) {
+        return err.to_compile_error().into();
+    }
+
+    table.expand(&queries)
+}
+
+struct QueryResolver {
+    dir: PathBuf,
+    query: String,
+    stack: Vec<String>,
+    files: Vec<PathBuf>,
+}
+
+impl QueryResolver {
+    fn path(&self, lang: &str) -> PathBuf {
+        self.dir.join(lang).join(format!("{}.scm", self.query))
+    }
+
+    fn resolve(&mut self, lang: &str) -> std::result::Result<String, String> {
+        if self.stack.iter().any(|parent| parent == lang) {
+            return Err(format!(
+                "queries inherit from each other: {} -> {lang}",
+                self.stack.join(" -> ")
+            ));
+        }
+
+        let path = self.path(lang);
+        let text = std::fs::read_to_string(&path)
+            .map_err(|err| format!("could not read {}: {err}", path.display()))?;
+
+        self.stack.push(lang.to_string());
+        self.files.push(path);
+
+        let mut resolved = String::with_capacity(text.len());
+        for line in text.lines() {
+            match inherited_languages(line) {
+                Some(parents) => {
+                    for parent in parents {
+                        let on_stack = self.stack.iter().any(|lang| lang == parent);
+                        if on_stack || !self.files.contains(&self.path(parent)) {
+                            resolved.push_str(&self.resolve(parent)?);
+                        }
+                    }
+                }
+                None => {
+                    resolved.push_str(line);
+                    resolved.push('\n');
+                }
+            }
+        }
 
-    quote! { include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/queries/", #lang, "/", #query, ".scm")) }.into()
+        self.stack.pop();
+        Ok(resolved)
+    }
+}
+
+fn inherited_languages(line: &str) -> Option<Vec<&str>> {
+    let rest = line.trim().strip_prefix(';')?;
+    let rest = rest.trim_start_matches(';').trim_start();
+    let languages = rest.strip_prefix("inherits:")?;
+
+    Some(
+        languages
+            .split(',')
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty())
+            .collect(),
+    )
 }
 
 struct IncludeOptional {
@@ -51,7 +190,7 @@ struct IncludeOptional {
 }
 
 impl Parse for IncludeOptional {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
+    fn parse(input: ParseStream) -> Result<Self> {
         let mut literals = Vec::new();
 
         // parse while we still have inputs.
@@ -63,7 +202,7 @@ impl Parse for IncludeOptional {
                 let ident: Ident = input.parse()?;
                 literals.push(ident.to_string());
             } else {
-                panic!("Should make error here but it's OK for now");
+                return Err(input.error("expected a string literal or an identifier"));
             }
 
             if input.is_empty() {
@@ -80,27 +219,33 @@ impl Parse for IncludeOptional {
     }
 }
 
-#[allow(unused)]
+/// Includes a file of the crate being compiled as a `&'static str`, or `""` if the file
+/// doesn't exist. The arguments are concatenated to a path relative to the Cargo.toml
+/// of that crate, identifiers are used as they are.
+///
+/// Proc macros can't tell cargo to watch a file that doesn't exist yet, so crates using
+/// this need a build script that prints `cargo:rerun-if-changed=<folder>`, which
+/// rebuilds them when files are added to the folder. Bazel does the same for files in
+/// `compile_data`.
+///
+/// Example:
+/// > include_project_file_optional!("queries/", "go", "/scip-tags.scm");
 #[proc_macro]
 pub fn include_project_file_optional(input: TokenStream) -> TokenStream {
-    if true {
-        todo!("don't use this yet, i have to come back to this");
-    }
-
     let literals = parse_macro_input!(input as IncludeOptional).literals;
 
     // project files are always relative to the Cargo.toml of the compiling project.
-    // let base = std::env::var("CARGO_MANIFEST_DIR").unwrap() + "/";
-    panic!("{:?}", std::env::var("CARGO_MANIFEST_DIR"));
-    let base = "/".to_string();
-    let filepath: PathBuf = literals.iter().fold(base, |acc, lit| acc + lit).into();
+    let base: PathBuf = std::env::var("CARGO_MANIFEST_DIR")
+        .expect("CARGO_MANIFEST_DIR must be set")
+        .into();
+    let filepath = base.join(literals.concat());
 
     if filepath.exists() {
         let filepath = filepath
             .to_str()
             .expect("Filepath must be expandable at this point");
 
-        quote! { include_str!(concat!(env!("CARGO_MANIFEST_DIR"), #filepath)) }.into()
+        quote! { include_str!(#filepath) }.into()
     } else {
         quote! { "" }.into()
     }
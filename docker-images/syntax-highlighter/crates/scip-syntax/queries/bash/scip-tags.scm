@@ -0,0 +1,13 @@
+;; Only what the script itself defines: its functions and the variables it sets at the
+;; top level. Everything in a function body is local to it.
+(program
+  (function_definition
+    name: (word) @descriptor.method @kind.function
+    body: (_) @local))
+
+(program (variable_assignment name: (variable_name) @descriptor.term @kind.variable))
+
+;; readonly VERSION=1, export PATH=...
+(program
+  (declaration_command
+    (variable_assignment name: (variable_name) @descriptor.term @kind.variable)))
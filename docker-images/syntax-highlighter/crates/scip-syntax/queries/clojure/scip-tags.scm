@@ -0,0 +1,35 @@
+;; Everything in a file is in the namespace of its ns form, e.g. `my.app.shapes`/
+((source
+   (list_lit . (sym_lit) @_ns . (sym_lit) @descriptor.namespace @kind.module)) @scope
+ (#eq? @_ns "ns"))
+
+;; Nothing in the body of a definition is a definition of the namespace, e.g. a def
+;; in a let
+((list_lit . (sym_lit) @_defn . (sym_lit) @descriptor.method @kind.function) @local
+ (#match? @_defn "^(defn|defn-|defmulti)$"))
+
+((list_lit . (sym_lit) @_defmacro . (sym_lit) @descriptor.method @kind.macro) @local
+ (#eq? @_defmacro "defmacro"))
+
+((list_lit . (sym_lit) @_def . (sym_lit) @descriptor.term @kind.variable) @local
+ (#eq? @_def "def"))
+
+;; (defprotocol Shape (area [this])), whose methods are the lists in its body
+((list_lit . (sym_lit) @_defprotocol . (sym_lit) @descriptor.type @kind.interface) @scope
+ (#eq? @_defprotocol "defprotocol"))
+
+((list_lit
+   . (sym_lit) @_defprotocol
+   . (sym_lit)
+   (list_lit . (sym_lit) @descriptor.method @kind.method))
+ (#eq? @_defprotocol "defprotocol"))
+
+;; (defrecord Circle [radius]), the fields are members of the type
+((list_lit . (sym_lit) @_defrecord . (sym_lit) @descriptor.type @kind.struct . (vec_lit)) @scope
+ (#match? @_defrecord "^(defrecord|deftype)$"))
+
+((list_lit
+   . (sym_lit) @_defrecord
+   . (sym_lit)
+   . (vec_lit (sym_lit) @descriptor.term @kind.field))
+ (#match? @_defrecord "^(defrecord|deftype)$"))
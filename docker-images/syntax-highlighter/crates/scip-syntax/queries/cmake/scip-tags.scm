@@ -0,0 +1,8 @@
+;; The first argument of function() and macro() is the name
+(function_def
+  (function_command
+    (argument_list . (argument) @descriptor.method @kind.function)))
+
+(macro_def
+  (macro_command
+    (argument_list . (argument) @descriptor.method @kind.macro)))
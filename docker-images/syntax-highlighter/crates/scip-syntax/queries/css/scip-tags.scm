@@ -0,0 +1,7 @@
+;; .btn and #header in selectors. Pseudo classes like :hover and :root are left out.
+(class_selector (class_name) @descriptor.term @kind.class)
+(id_selector (id_name) @descriptor.term @kind.object)
+
+;; --main-color: #333;
+((declaration (property_name) @descriptor.term @kind.variable) @enclosing
+ (#match? @descriptor.term "^--"))
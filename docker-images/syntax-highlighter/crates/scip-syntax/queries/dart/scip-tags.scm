@@ -0,0 +1,31 @@
+(function_body) @local
+
+(class_definition
+  name: (identifier) @descriptor.type @kind.class
+  (class_body) @scope)
+
+(mixin_declaration
+  . (identifier) @descriptor.type @kind.class
+  (class_body) @scope)
+
+;; Members of an extension are members of the type it extends, like those of an
+;; extension in Swift. Its own name, if any, is never used to refer to them.
+(extension_declaration
+  (type_identifier) @descriptor.type
+  (extension_body) @scope)
+
+(enum_declaration
+  name: (identifier) @descriptor.type @kind.enum
+  (enum_body) @scope)
+
+(enum_constant name: (identifier) @descriptor.term @kind.enummember)
+
+;; Methods, and abstract methods which are declarations without a body
+(class_body
+  (method_signature (function_signature name: (identifier) @descriptor.method @kind.method)))
+(class_body
+  (declaration (function_signature name: (identifier) @descriptor.method @kind.method)))
+(extension_body
+  (method_signature (function_signature name: (identifier) @descriptor.method @kind.method)))
+
+(program (function_signature name: (identifier) @descriptor.method @kind.function))
@@ -0,0 +1,70 @@
+;; Everything is a call in Elixir, so definitions are calls of the def* macros. The name
+;; of a module is the whole alias, e.g. `Greeter.Formal`.
+
+((call
+   target: (identifier) @_defmodule
+   (arguments (alias) @descriptor.namespace @kind.module)
+   (do_block) @scope)
+ (#eq? @_defmodule "defmodule"))
+
+((call
+   target: (identifier) @_defprotocol
+   (arguments (alias) @descriptor.namespace @kind.interface)
+   (do_block) @scope)
+ (#eq? @_defprotocol "defprotocol"))
+
+;; defimpl Shape, for: Circle. The functions of an implementation are members of the
+;; type it is for, like those of an impl in Rust.
+((call
+   target: (identifier) @_defimpl
+   (arguments
+     (alias)
+     (keywords (pair value: (alias) @descriptor.namespace)))
+   (do_block) @scope)
+ (#eq? @_defimpl "defimpl"))
+
+;; Functions are told apart by their arity, so greet/1 and greet/2 are greet(1). and
+;; greet(2). A function without parentheses has no arguments, its name is the @arity.
+((call
+   target: (identifier) @_def
+   (arguments
+     [
+       (call target: (identifier) @descriptor.method @kind.function (arguments) @arity)
+       (binary_operator
+         left: (call target: (identifier) @descriptor.method @kind.function (arguments) @arity)
+         operator: "when")
+       (identifier) @descriptor.method @kind.function @arity
+     ]))
+ (#match? @_def "^(def|defp|defdelegate|defguard|defguardp)$"))
+
+((call
+   target: (identifier) @_defmacro
+   (arguments
+     [
+       (call target: (identifier) @descriptor.method @kind.macro (arguments) @arity)
+       (binary_operator
+         left: (call target: (identifier) @descriptor.method @kind.macro (arguments) @arity)
+         operator: "when")
+       (identifier) @descriptor.method @kind.macro @arity
+     ]))
+ (#match? @_defmacro "^(defmacro|defmacrop)$"))
+
+;; Nothing in the body of a function or macro is a definition of the module, e.g. defs
+;; in a quote
+((call
+   target: (identifier) @_def
+   (do_block) @local)
+ (#match? @_def "^(def|defp|defmacro|defmacrop)$"))
+
+;; defstruct [:name, title: "Dr."], the fields of the struct of the module
+((call
+   target: (identifier) @_defstruct
+   (arguments (list (atom) @descriptor.term @kind.field)))
+ (#eq? @_defstruct "defstruct")
+ (#transform! "^:" ""))
+
+((call
+   target: (identifier) @_defstruct
+   (arguments (list (keywords (pair key: (keyword) @descriptor.term @kind.field)))))
+ (#eq? @_defstruct "defstruct")
+ (#transform! ":\\s*$" ""))
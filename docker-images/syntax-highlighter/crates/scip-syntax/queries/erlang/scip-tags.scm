@@ -0,0 +1,20 @@
+;; Everything in a file is in its module, e.g. -module(greeter).
+((source_file (module_attribute name: (atom) @descriptor.namespace @kind.module)) @scope)
+
+;; Functions are told apart by their arity, so greet/1 and greet/2 are greet(1). and
+;; greet(2). Every clause of a function is a definition.
+(function_clause
+  name: (atom) @descriptor.method @kind.function
+  args: (expr_args) @arity
+  body: (_) @local)
+
+;; -record(point, {x, y}).
+(record_decl
+  name: (atom) @descriptor.type
+  (record_field name: (atom) @descriptor.term @kind.field))
+
+(record_decl name: (atom) @descriptor.type @kind.struct)
+
+;; -type shape() :: ...
+(type_alias (type_name name: (atom) @descriptor.type @kind.typealias))
+(opaque (type_name name: (atom) @descriptor.type @kind.typealias))
@@ -78,8 +78,7 @@
 ;
 
 ;; TODO: These may not make much sense to have for locals... {{{
-((package_identifier) @reference
-  (set! reference.kind "namespace"))
+(package_identifier) @reference
 
 (package_clause
    (package_identifier) @definition.namespace)
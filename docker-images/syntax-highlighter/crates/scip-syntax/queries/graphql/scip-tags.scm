@@ -0,0 +1,30 @@
+;; The fields of Query, Mutation and Subscription are the operations of the schema, the
+;; fields of any other type are fields
+(object_type_definition (name) @descriptor.type @kind.struct (fields_definition) @scope)
+
+((object_type_definition
+   (name) @_type
+   (fields_definition (field_definition (name) @descriptor.term @kind.field)))
+ (#not-match? @_type "^(Query|Mutation|Subscription)$"))
+
+((object_type_definition
+   (name) @_type
+   (fields_definition (field_definition (name) @descriptor.method @kind.method)))
+ (#match? @_type "^(Query|Mutation|Subscription)$"))
+
+(interface_type_definition (name) @descriptor.type @kind.interface (fields_definition) @scope)
+(interface_type_definition
+  (fields_definition (field_definition (name) @descriptor.term @kind.field)))
+
+(input_object_type_definition (name) @descriptor.type @kind.struct (input_fields_definition) @scope)
+(input_fields_definition (input_value_definition (name) @descriptor.term @kind.field))
+
+(enum_type_definition (name) @descriptor.type @kind.enum (enum_values_definition) @scope)
+(enum_value_definition (enum_value) @descriptor.term @kind.enummember)
+
+(union_type_definition (name) @descriptor.type @kind.union)
+(scalar_type_definition (name) @descriptor.type @kind.typealias)
+
+;; Named queries, mutations and subscriptions of a document, e.g. query GetUser { ... }
+(operation_definition (name) @descriptor.method @kind.function)
+(fragment_definition (fragment_name (name) @descriptor.term @kind.variable))
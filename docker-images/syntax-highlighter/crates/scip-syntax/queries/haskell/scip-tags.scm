@@ -0,0 +1,63 @@
+;; Everything in a file is in its module, the header is the only module name that is a
+;; direct child of the file
+((haskell (module) @descriptor.namespace @kind.module) @scope)
+
+;; Top level functions are defined once, by their signature, or by their first equation
+;; if they don't have one. An equation is the first one unless the declaration right
+;; before it is a signature or an equation of the same name.
+(haskell (signature name: (variable) @descriptor.method @kind.function))
+
+(haskell . (function name: (variable) @descriptor.method @kind.function))
+
+((haskell
+   (_) @_previous
+   .
+   (function name: (variable) @descriptor.method @kind.function))
+ (#filter! @_previous "signature" "function"))
+
+((haskell
+   [
+     (signature name: (variable) @_previous)
+     (function name: (variable) @_previous)
+   ]
+   .
+   (function name: (variable) @descriptor.method @kind.function))
+ (#not-eq? @_previous @descriptor.method))
+
+;; data Shape = Circle Double | Square Double
+(adt . (type) @descriptor.type @kind.enum) @scope
+(data_constructor . (constructor) @descriptor.term @kind.enummember)
+
+;; data Point = Point { x :: Double, y :: Double }, the fields are members of the type
+(data_constructor_record . (constructor) @descriptor.term @kind.enummember)
+(record_fields (field (variable) @descriptor.term @kind.field))
+
+(newtype . (type) @descriptor.type @kind.struct)
+(type_alias . (type) @descriptor.type @kind.typealias)
+
+;; Type classes, whose methods are the signatures in their body
+(class
+  (class_head (class_name (type) @descriptor.type @kind.interface))
+  (class_body) @scope)
+(class_body (signature name: (variable) @descriptor.method @kind.method))
+
+;; instance Show Shape. The methods of an instance are members of the type it is for,
+;; like those of an impl in Rust. They are direct children of the instance, after `where`.
+(instance
+  (instance_head (type_name (type) @descriptor.type))) @scope
+
+;; Like top level functions, only the first equation of a method defines it
+((instance
+   (_) @_previous
+   .
+   (function name: (variable) @descriptor.method @kind.method))
+ (#filter! @_previous "signature" "function"))
+
+((instance
+   [
+     (signature name: (variable) @_previous)
+     (function name: (variable) @_previous)
+   ]
+   .
+   (function name: (variable) @descriptor.method @kind.method))
+ (#not-eq? @_previous @descriptor.method))
@@ -0,0 +1,19 @@
+;; resource "aws_s3_bucket" "logs" { ... } is resource.aws_s3_bucket.logs, and the
+;; attributes and blocks in its body are below it
+(block
+  . (identifier) @descriptor.term
+  . (block_start)) @scope @kind.object
+
+(block
+  . (identifier) @descriptor.term
+  . (string_lit (template_literal) @descriptor.term)
+  . (block_start)) @scope @kind.object
+
+(block
+  . (identifier) @descriptor.term
+  . (string_lit (template_literal) @descriptor.term)
+  . (string_lit (template_literal) @descriptor.term)
+  . (block_start)) @scope @kind.object
+
+;; bucket = "logs"
+(attribute (identifier) @descriptor.term) @enclosing @kind.property
@@ -0,0 +1,8 @@
+;; Scripts and styles are tagged like javascript and css files
+((script_element
+  (raw_text) @injection.content)
+ (#set! injection.language "javascript"))
+
+((style_element
+  (raw_text) @injection.content)
+ (#set! injection.language "css"))
@@ -0,0 +1,14 @@
+;; "scripts": { "build": "tsc" } is scripts.build. Objects in arrays share the key of
+;; the array, so "files": [{ "glob": "*.ts" }] is files.glob.
+(pair
+  key: (string (string_content) @descriptor.term)
+  value: (object)) @scope @kind.object
+
+(pair
+  key: (string (string_content) @descriptor.term)
+  value: (array)) @scope @kind.property
+
+(pair
+  key: (string (string_content) @descriptor.term)
+  value: (_) @_value
+  (#filter! @_value "object" "array")) @enclosing @kind.property
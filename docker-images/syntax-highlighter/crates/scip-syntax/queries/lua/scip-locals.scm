@@ -0,0 +1,38 @@
+;; Only `local` declarations, function parameters and loop variables are locals,
+;; any other assignment sets a global or a field of a table.
+
+[
+  (do_statement)
+  (while_statement)
+  (repeat_statement)
+  (if_statement)
+  (for_statement)
+  (function_declaration)
+  (function_definition)
+] @scope
+
+;; local x, y = 1, 2
+(variable_declaration
+  (assignment_statement
+    (variable_list (identifier) @definition.var)))
+
+;; local x
+(variable_declaration
+  (variable_list (identifier) @definition.var))
+
+;; The name of a local function is in the range of the function, but can be called
+;; after it as well as from its body
+(function_declaration
+  "local"
+  name: ((identifier) @definition.function
+         (#set! "scope" "parent")))
+
+(parameters (identifier) @definition.var)
+
+(for_generic_clause
+  (variable_list (identifier) @definition.var))
+
+(for_numeric_clause
+  name: (identifier) @definition.var)
+
+(identifier) @reference
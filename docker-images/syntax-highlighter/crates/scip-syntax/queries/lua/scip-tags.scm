@@ -0,0 +1,30 @@
+;; Only what a file defines at the top level, everything else is local to a function.
+;; Modules and classes are tables, so their functions are tagged as members of the
+;; table they are assigned to.
+
+(chunk (function_declaration name: (identifier) @descriptor.method @kind.function))
+
+;; function M.new(...)
+(chunk
+  (function_declaration
+    name: (dot_index_expression
+            table: (identifier) @descriptor.type
+            field: (identifier) @descriptor.method @kind.function)))
+
+;; function M:draw(...)
+(chunk
+  (function_declaration
+    name: (method_index_expression
+            table: (identifier) @descriptor.type
+            method: (identifier) @descriptor.method @kind.method)))
+
+;; local M = {}
+(chunk
+  (variable_declaration
+    (assignment_statement
+      (variable_list (identifier) @descriptor.term @kind.variable))))
+
+;; VERSION = "1.0"
+(chunk
+  (assignment_statement
+    (variable_list (identifier) @descriptor.term @kind.variable)))
@@ -0,0 +1,9 @@
+;; Targets and variables. Special targets like .PHONY and pattern rules like %.o
+;; aren't things anyone looks up.
+((rule (targets (word) @descriptor.method @kind.function))
+ (#not-match? @descriptor.method "^\\.|%"))
+
+(variable_assignment name: (word) @descriptor.term @kind.variable)
+
+;; define NAME ... endef
+(define_directive name: (word) @descriptor.term @kind.variable)
@@ -0,0 +1,31 @@
+; inherits: c
+
+;; @interface Shape : NSObject and @implementation Shape. Methods are members of the
+;; class whichever of the two declares them.
+(class_interface
+  "@interface" . (identifier) @descriptor.type @kind.class
+  !category) @scope
+
+(class_implementation
+  "@implementation" . (identifier) @descriptor.type @kind.class
+  !category) @scope
+
+;; Members of a category are members of the class it extends, like those of an
+;; extension in Swift
+(class_interface
+  "@interface" . (identifier) @descriptor.type
+  category: (identifier)) @scope
+
+(class_implementation
+  "@implementation" . (identifier) @descriptor.type
+  category: (identifier)) @scope
+
+(protocol_declaration
+  "@protocol" . (identifier) @descriptor.type @kind.interface) @scope
+
+;; Only the first part of a selector, e.g. moveTo of moveTo:y:
+(method_declaration (method_type) . (identifier) @descriptor.method @kind.method)
+
+(method_definition
+  (method_type) . (identifier) @descriptor.method @kind.method
+  (compound_statement) @local)
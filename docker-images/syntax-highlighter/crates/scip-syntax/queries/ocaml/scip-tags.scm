@@ -0,0 +1,35 @@
+;; module Shape = struct ... end
+(module_binding
+  (module_name) @descriptor.namespace @kind.module
+  (structure) @scope)
+
+;; module type SHAPE = sig ... end, the closest thing to an interface
+(module_type_definition
+  (module_type_name) @descriptor.type @kind.interface
+  (signature) @scope)
+(signature (value_specification (value_name) @descriptor.method @kind.method))
+
+;; Only functions, let bindings in their body are local to them
+(value_definition
+  (let_binding
+    pattern: (value_name) @descriptor.method @kind.function
+    (parameter)
+    body: (_) @local))
+(value_definition
+  (let_binding
+    pattern: (value_name) @descriptor.method @kind.function
+    .
+    body: [(fun_expression) (function_expression)] @local))
+(external (value_name) @descriptor.method @kind.function)
+
+;; type point = { x : float; y : float }
+(type_binding
+  (type_constructor) @descriptor.type @kind.struct
+  (record_declaration) @scope)
+(record_declaration (field_declaration (field_name) @descriptor.term @kind.field))
+
+;; type shape = Circle of float | Square of float
+(type_binding
+  (type_constructor) @descriptor.type @kind.enum
+  (variant_declaration) @scope)
+(variant_declaration (constructor_declaration (constructor_name) @descriptor.term @kind.enummember))
@@ -0,0 +1,22 @@
+;; Everything in a file is in its package, e.g. `acme.shapes.v1`/
+((source_file (package (full_ident) @descriptor.namespace @kind.package)) @scope)
+
+;; Messages may be nested, so the fields of a message are in the scope of its body
+(message
+  (message_name (identifier) @descriptor.type @kind.struct)
+  (message_body) @scope)
+
+(message_body (field (identifier) @descriptor.term @kind.field))
+(message_body (map_field (identifier) @descriptor.term @kind.field))
+(message_body (oneof (oneof_field (identifier) @descriptor.term @kind.field)))
+
+(enum
+  (enum_name (identifier) @descriptor.type @kind.enum)
+  (enum_body) @scope)
+
+(enum_body (enum_field (identifier) @descriptor.term @kind.enummember))
+
+;; Services have no body node, so the whole service is the scope of its RPCs
+((service (service_name (identifier) @descriptor.type @kind.interface)) @scope)
+
+(rpc (rpc_name (identifier) @descriptor.method @kind.method))
@@ -0,0 +1,4 @@
+(mixin_statement (name) @descriptor.method @kind.function)
+(function_statement (name) @descriptor.method @kind.function)
+
+; inherits: css
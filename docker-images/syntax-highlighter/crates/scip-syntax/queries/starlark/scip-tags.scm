@@ -0,0 +1,22 @@
+;; Macros are the functions of a .bzl file, rules and providers are what it assigns the
+;; result of rule(), provider() etc. to.
+(module (function_definition name: (identifier) @descriptor.method @kind.function body: (_) @local))
+
+(expression_statement
+  (assignment
+    left: (identifier) @descriptor.method @kind.function
+    right: (call function: (identifier) @_constructor))
+  (#match? @_constructor "^(rule|repository_rule|aspect|module_extension)$"))
+
+(expression_statement
+  (assignment
+    left: (identifier) @descriptor.type @kind.struct
+    right: (call function: (identifier) @_constructor))
+  (#eq? @_constructor "provider"))
+
+;; DEFAULT_VISIBILITY = [...]
+(expression_statement
+  (assignment
+    left: (identifier) @descriptor.term @kind.variable
+    right: (_) @_value)
+  (#not-match? @_value "^(rule|repository_rule|aspect|module_extension|provider)\\("))
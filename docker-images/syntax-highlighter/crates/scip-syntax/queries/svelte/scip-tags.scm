@@ -0,0 +1,26 @@
+;; Scripts and styles are tagged like files of their language, which makes the exports
+;; of the component script its symbols. <script lang="ts"> is typescript, <style
+;; lang="scss"> is scss.
+((script_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#match? @_tag "lang=[\"']?(ts|typescript)[\"' >]")
+ (#set! injection.language "typescript"))
+
+((script_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#not-match? @_tag "lang=[\"']?(ts|typescript)[\"' >]")
+ (#set! injection.language "javascript"))
+
+((style_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#match? @_tag "lang=[\"']?scss[\"' >]")
+ (#set! injection.language "scss"))
+
+((style_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#not-match? @_tag "lang=")
+ (#set! injection.language "css"))
@@ -0,0 +1,63 @@
+(class_declaration
+  declaration_kind: "class"
+  name: (type_identifier) @descriptor.type @kind.class
+  body: (_) @scope)
+
+(class_declaration
+  declaration_kind: "actor"
+  name: (type_identifier) @descriptor.type @kind.class
+  body: (_) @scope)
+
+(class_declaration
+  declaration_kind: "struct"
+  name: (type_identifier) @descriptor.type @kind.struct
+  body: (_) @scope)
+
+(class_declaration
+  declaration_kind: "enum"
+  name: (type_identifier) @descriptor.type @kind.enum
+  body: (_) @scope)
+
+;; Members of an extension are members of the extended type, like those of an impl in
+;; Rust. It can extend a class, struct, enum or protocol, so it has no kind.
+(class_declaration
+  declaration_kind: "extension"
+  name: (user_type (type_identifier) @descriptor.type .)
+  body: (_) @scope)
+
+(protocol_declaration
+  name: (type_identifier) @descriptor.type @kind.interface
+  body: (_) @scope)
+
+(protocol_function_declaration name: (simple_identifier) @descriptor.method @kind.method)
+(protocol_property_declaration
+  name: (pattern bound_identifier: (simple_identifier) @descriptor.term @kind.property))
+
+(class_body
+  (function_declaration
+    name: (simple_identifier) @descriptor.method @kind.method
+    body: (_) @local))
+
+(class_body (init_declaration "init" @descriptor.method @kind.constructor body: (_) @local))
+
+(class_body
+  (property_declaration
+    name: (pattern bound_identifier: (simple_identifier) @descriptor.term @kind.property)))
+
+(enum_class_body
+  (function_declaration
+    name: (simple_identifier) @descriptor.method @kind.method
+    body: (_) @local))
+
+(enum_entry name: (simple_identifier) @descriptor.term @kind.enummember)
+
+(source_file
+  (function_declaration
+    name: (simple_identifier) @descriptor.method @kind.function
+    body: (_) @local))
+
+(source_file
+  (property_declaration
+    name: (pattern bound_identifier: (simple_identifier) @descriptor.term @kind.variable)))
+
+(typealias_declaration name: (type_identifier) @descriptor.type @kind.typealias)
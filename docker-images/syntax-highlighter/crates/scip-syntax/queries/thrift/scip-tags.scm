@@ -0,0 +1,19 @@
+;; Thrift has a namespace per target language, so definitions are at the top level
+
+(struct_definition . (identifier) @descriptor.type @kind.struct) @scope
+(union_definition . (identifier) @descriptor.type @kind.union) @scope
+(exception_definition . (identifier) @descriptor.type @kind.class) @scope
+
+;; Only the fields of types, the parameters of functions are fields too
+(struct_definition (field (identifier) @descriptor.term @kind.field))
+(union_definition (field (identifier) @descriptor.term @kind.field))
+(exception_definition (field (identifier) @descriptor.term @kind.field))
+
+(enum_definition . (identifier) @descriptor.type @kind.enum) @scope
+(enum_definition (_ (identifier) @descriptor.term @kind.enummember))
+
+(service_definition . (identifier) @descriptor.type @kind.interface) @scope
+(function_definition (identifier) @descriptor.method @kind.method)
+
+(typedef_definition (identifier) @descriptor.type @kind.typealias)
+(const_definition (identifier) @descriptor.term @kind.constant)
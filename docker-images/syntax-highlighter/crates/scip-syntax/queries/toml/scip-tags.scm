@@ -0,0 +1,38 @@
+;; [package], [target.'cfg(unix)'.dependencies] and [[bin]] are scopes for the keys
+;; below them. Headers with more than three dotted keys are not symbols.
+([(table . [(bare_key) (quoted_key)] @descriptor.term)
+  (table_array_element . [(bare_key) (quoted_key)] @descriptor.term)]) @scope @kind.object
+
+([(table
+    (dotted_key
+      . [(bare_key) (quoted_key)] @descriptor.term
+      [(bare_key) (quoted_key)] @descriptor.term))
+  (table_array_element
+    (dotted_key
+      . [(bare_key) (quoted_key)] @descriptor.term
+      [(bare_key) (quoted_key)] @descriptor.term))]) @scope @kind.object
+
+([(table
+    (dotted_key
+      (dotted_key
+        . [(bare_key) (quoted_key)] @descriptor.term
+        [(bare_key) (quoted_key)] @descriptor.term)
+      [(bare_key) (quoted_key)] @descriptor.term))
+  (table_array_element
+    (dotted_key
+      (dotted_key
+        . [(bare_key) (quoted_key)] @descriptor.term
+        [(bare_key) (quoted_key)] @descriptor.term)
+      [(bare_key) (quoted_key)] @descriptor.term))]) @scope @kind.object
+
+;; name = "demo" and a.b = 1, but not a.b.c = 1
+(pair . [(bare_key) (quoted_key)] @descriptor.term (_) @_value
+  (#filter! @_value "inline_table")) @enclosing @kind.property
+
+(pair
+  (dotted_key
+    . [(bare_key) (quoted_key)] @descriptor.term
+    [(bare_key) (quoted_key)] @descriptor.term)) @enclosing @kind.property
+
+;; inline = { path = "src/main.rs" }
+(pair . [(bare_key) (quoted_key)] @descriptor.term (inline_table)) @scope @kind.object
@@ -1,4 +1,4 @@
-;;include javascript
+; inherits: javascript
 
 (module name: (string (string_fragment) @descriptor.namespace @kind.module) body: (_) @scope)
 
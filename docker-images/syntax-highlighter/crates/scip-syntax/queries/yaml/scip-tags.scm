@@ -0,0 +1,18 @@
+;; spec: { template: { containers: [] } } is spec.template.containers, in block or flow
+;; style. Mappings in sequences share the key of the sequence. Quoted keys keep their
+;; quotes and merge keys (<<) aren't symbols.
+(_
+  key: (flow_node [(plain_scalar) (double_quote_scalar) (single_quote_scalar)] @descriptor.term)
+  value: [(block_node (block_mapping)) (flow_node (flow_mapping))]
+  (#not-eq? @descriptor.term "<<")) @scope @kind.object
+
+(_
+  key: (flow_node [(plain_scalar) (double_quote_scalar) (single_quote_scalar)] @descriptor.term)
+  value: [(block_node (block_sequence)) (flow_node (flow_sequence))]
+  (#not-eq? @descriptor.term "<<")) @scope @kind.property
+
+(_
+  key: (flow_node [(plain_scalar) (double_quote_scalar) (single_quote_scalar)] @descriptor.term)
+  value: [(block_node (block_scalar)) (flow_node (_) @_value .)]
+  (#filter! @_value "flow_mapping" "flow_sequence")
+  (#not-eq? @descriptor.term "<<")) @enclosing @kind.property
@@ -11,7 +11,7 @@ use scip::types::{descriptor::Suffix, symbol_information, Descriptor};
 use scip_treesitter_languages::parsers::BundledParser;
 use serde::{Deserialize, Serialize};
 
-use crate::{get_globals, globals::Scope};
+use crate::{encoding, get_globals, globals::Scope};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "command", rename_all = "kebab-case")]
@@ -190,11 +190,14 @@ pub fn generate_tags<W: std::io::Write>(
     file_data: &[u8],
 ) -> Option<()> {
     let path = path::Path::new(&filename);
-    let extension = path.extension()?.to_str()?;
     let filepath = path.file_name()?.to_str()?;
 
-    let parser = BundledParser::get_parser_from_extension(extension)?;
-    let (root_scope, _) = match get_globals(parser, file_data)? {
+    let parser = BundledParser::get_parser_from_file_name(filepath)?;
+
+    // Tags only refer to lines, which transcoding doesn't change, so there is nothing
+    // to map back for files that aren't UTF-8.
+    let decoded = encoding::decode(file_data, None).ok()?;
+    let (root_scope, _) = match get_globals(parser, decoded.text.as_bytes())? {
         Ok(vals) => vals,
         Err(err) => {
             // TODO: Not sure I want to keep this or not
@@ -314,4 +317,18 @@ fn other() -> bool { false }
 
         Ok(())
     }
+
+    #[test]
+    fn test_generate_tags_for_latin1_file() -> Result<()> {
+        let (file, _, _) = encoding_rs::WINDOWS_1252
+            .encode("// Fonctions pour la crème brûlée, à préparer la veille\nfn préparer() {}\n");
+
+        let mut output = BufWriter::new(Vec::new());
+        generate_tags(&mut output, "main.rs".to_string(), &file).expect("to generate tags");
+
+        let output = String::from_utf8(output.into_inner()?)?;
+        assert!(output.contains(r#""name":"préparer""#), "{output}");
+
+        Ok(())
+    }
 }
@@ -0,0 +1,240 @@
+//! Decoding of source files that are not UTF-8.
+//!
+//! Everything downstream (tree-sitter, syntect, the SCIP documents we return) works on
+//! UTF-8, so files are transcoded first. The encoding comes from, in this order:
+//! a byte order mark, an explicit charset from the client, and otherwise a guess.
+
+use anyhow::{anyhow, Result};
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingSource {
+    #[serde(rename = "bom")]
+    Bom,
+
+    #[serde(rename = "charset")]
+    Charset,
+
+    // Valid UTF-8, or a statistical guess for anything else
+    #[serde(rename = "detected")]
+    Detected,
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedText {
+    pub text: String,
+    pub encoding: &'static Encoding,
+    pub source: EncodingSource,
+
+    // Some bytes were not valid in the encoding and were replaced with U+FFFD
+    pub had_errors: bool,
+
+    bom_len: usize,
+}
+
+/// Decodes `bytes` to UTF-8. Fails only for an unknown `charset` label.
+pub fn decode(bytes: &[u8], charset: Option<&str>) -> Result<DecodedText> {
+    let (encoding, source, bom_len) = if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        (encoding, EncodingSource::Bom, bom_len)
+    } else if let Some(label) = charset {
+        let encoding = Encoding::for_label(label.trim().as_bytes())
+            .ok_or_else(|| anyhow!("unknown charset {label:?}"))?;
+        (encoding, EncodingSource::Charset, 0)
+    } else {
+        (guess_encoding(bytes), EncodingSource::Detected, 0)
+    };
+
+    let (text, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+    Ok(DecodedText {
+        text: text.into_owned(),
+        encoding,
+        source,
+        had_errors,
+        bom_len,
+    })
+}
+
+fn guess_encoding(bytes: &[u8]) -> &'static Encoding {
+    // Most files are UTF-8, and that check is a lot cheaper than the detector
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    detector.guess(None, true)
+}
+
+impl DecodedText {
+    pub fn encoding_name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    /// Builds the table to map positions in [`DecodedText::text`] back to `original`,
+    /// which must be the bytes this text was decoded from.
+    pub fn position_map(&self, original: &[u8]) -> PositionMap {
+        // Feed the decoder one byte at a time, so we know which bytes produced which
+        // characters. This also works for stateful encodings (e.g. ISO-2022-JP escape
+        // sequences belong to the character that follows them) and for invalid bytes.
+        let mut decoder = self.encoding.new_decoder_without_bom_handling();
+        let mut decoded = String::with_capacity(self.text.len());
+        let mut offsets = Vec::with_capacity(self.text.len() + 1);
+
+        let src = &original[self.bom_len.min(original.len())..];
+        let mut pending_start = self.bom_len;
+        for (idx, byte) in src.iter().enumerate() {
+            let before = decoded.len();
+            decoded.reserve(decoder.max_utf8_buffer_length(1).unwrap_or(16));
+            let _ = decoder.decode_to_string(std::slice::from_ref(byte), &mut decoded, false);
+
+            if decoded.len() > before {
+                let current = self.bom_len + idx;
+                push_offsets(&mut offsets, &decoded[before..], pending_start, current);
+                pending_start = current + 1;
+            }
+        }
+
+        let before = decoded.len();
+        decoded.reserve(decoder.max_utf8_buffer_length(0).unwrap_or(16));
+        let _ = decoder.decode_to_string(&[], &mut decoded, true);
+        push_offsets(
+            &mut offsets,
+            &decoded[before..],
+            pending_start,
+            original.len(),
+        );
+
+        offsets.push(original.len());
+
+        let mut line_starts = vec![0];
+        for (idx, c) in decoded.chars().enumerate() {
+            if c == '\n' {
+                line_starts.push(idx + 1);
+            }
+        }
+
+        PositionMap {
+            offsets,
+            line_starts,
+        }
+    }
+}
+
+// The first of the characters that a byte completed starts where the bytes that
+// weren't decoded yet start. Any others (e.g. a replacement character followed by an
+// ASCII character) are attributed to the byte itself.
+fn push_offsets(offsets: &mut Vec<usize>, chars: &str, first: usize, rest: usize) {
+    for (idx, _) in chars.char_indices() {
+        offsets.push(if idx == 0 { first } else { rest });
+    }
+}
+
+/// Maps (line, character) positions in decoded text to (line, byte) positions in the
+/// original encoding.
+#[derive(Debug, Clone)]
+pub struct PositionMap {
+    // Byte offset in the original for every character, plus the end
+    offsets: Vec<usize>,
+
+    // Character index at which every line starts
+    line_starts: Vec<usize>,
+}
+
+impl PositionMap {
+    /// The byte column in the original encoding of the character at `column`. Columns
+    /// past the end of the line are clamped to the end of the line.
+    pub fn original_column(&self, line: usize, column: usize) -> usize {
+        let line_start = match self.line_starts.get(line) {
+            Some(start) => *start,
+            None => return 0,
+        };
+        let line_end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.offsets.len() - 1, |next| next - 1);
+
+        let idx = (line_start + column).min(line_end);
+        self.offsets[idx] - self.offsets[line_start]
+    }
+
+    /// Maps a SCIP range from character columns to byte columns in the original encoding.
+    pub fn original_range(&self, range: &[i32]) -> Vec<i32> {
+        let map = |line: i32, column: i32| {
+            self.original_column(line.max(0) as usize, column.max(0) as usize) as i32
+        };
+
+        match range {
+            [line, start, end] => vec![*line, map(*line, *start), map(*line, *end)],
+            [start_line, start, end_line, end] => vec![
+                *start_line,
+                map(*start_line, *start),
+                *end_line,
+                map(*end_line, *end),
+            ],
+            _ => range.to_vec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use encoding_rs::{SHIFT_JIS, UTF_16LE, WINDOWS_1252};
+
+    use super::*;
+
+    #[test]
+    fn test_utf8_is_passed_through() {
+        let decoded = decode("fn main() {}".as_bytes(), None).unwrap();
+        assert_eq!(decoded.encoding, UTF_8);
+        assert_eq!(decoded.source, EncodingSource::Detected);
+        assert_eq!(decoded.text, "fn main() {}");
+    }
+
+    #[test]
+    fn test_bom_wins_over_charset() {
+        // encoding_rs only decodes UTF-16, so encode it by hand
+        let mut utf16 = vec![0xFF, 0xFE];
+        for unit in "x = 1\n".encode_utf16() {
+            utf16.extend(unit.to_le_bytes());
+        }
+
+        let decoded = decode(&utf16, Some("latin1")).unwrap();
+        assert_eq!(decoded.encoding, UTF_16LE);
+        assert_eq!(decoded.source, EncodingSource::Bom);
+        assert_eq!(decoded.text, "x = 1\n");
+
+        let map = decoded.position_map(&utf16);
+        assert_eq!(map.original_range(&[0, 4, 5]), vec![0, 8, 10]);
+    }
+
+    #[test]
+    fn test_charset() {
+        let (bytes, _, _) = SHIFT_JIS.encode("// こんにちは\nlet x = 1;\n");
+        let decoded = decode(&bytes, Some("Shift_JIS")).unwrap();
+        assert_eq!(decoded.encoding, SHIFT_JIS);
+        assert_eq!(decoded.text, "// こんにちは\nlet x = 1;\n");
+        assert!(!decoded.had_errors);
+
+        let map = decoded.position_map(&bytes);
+        // Every kana is two bytes in Shift_JIS
+        assert_eq!(map.original_column(0, 4), 5);
+        assert_eq!(map.original_column(0, 8), 13);
+        assert_eq!(map.original_column(1, 4), 4);
+        // Clamped to the end of the line
+        assert_eq!(map.original_column(0, 100), 13);
+
+        assert!(decode(&bytes, Some("not-a-charset")).is_err());
+    }
+
+    #[test]
+    fn test_latin1_is_detected() {
+        let (bytes, _, _) =
+            WINDOWS_1252.encode("# Café crème brûlée, déjà vu à la française\nprint('é')\n");
+        let decoded = decode(&bytes, None).unwrap();
+        assert_eq!(decoded.encoding, WINDOWS_1252);
+        assert_eq!(decoded.source, EncodingSource::Detected);
+        assert!(decoded.text.starts_with("# Café"));
+    }
+}
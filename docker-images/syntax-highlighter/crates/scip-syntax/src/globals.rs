@@ -3,8 +3,9 @@ use bitvec::prelude::*;
 use protobuf::Enum;
 use scip::types::{symbol_information, Descriptor, Document, Occurrence, SymbolInformation};
 use scip_treesitter::types::PackedRange;
+use scip_treesitter_languages::pool;
 
-use crate::languages::TagConfiguration;
+use crate::languages::{Injections, TagConfiguration};
 
 #[derive(Debug)]
 pub struct Scope {
@@ -49,6 +50,17 @@ impl Scope {
         }
     }
 
+    // The symbols of code in another language that is part of this document
+    fn insert_embedded(&mut self, embedded: Scope) {
+        for scope in embedded.children {
+            self.insert_scope(scope);
+        }
+
+        for global in embedded.globals {
+            self.insert_global(global);
+        }
+    }
+
     pub fn into_document(&mut self, hint: usize, base_descriptors: Vec<Descriptor>) -> Document {
         let mut descriptor_stack = base_descriptors;
 
@@ -148,13 +160,14 @@ pub fn parse_tree<'a>(
     tree: &'a tree_sitter::Tree,
     source_bytes: &'a [u8],
 ) -> Result<(Scope, usize)> {
-    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut cursor = pool::query_cursor();
 
     let root_node = tree.root_node();
     let capture_names = config.tag_query.capture_names();
 
     let mut scopes = vec![];
     let mut globals = vec![];
+    let mut injections = Injections::default();
 
     let mut local_ranges = BitVec::<u8, Msb0>::repeat(false, source_bytes.len());
 
@@ -164,12 +177,18 @@ pub fn parse_tree<'a>(
             continue;
         }
 
+        if let Some((language, range)) = config.injection(&m) {
+            injections.add(language, range);
+            continue;
+        }
+
         let mut node = None;
         let mut enclosing_node = None;
         let mut scope = None;
         let mut local_range = None;
         let mut descriptors = vec![];
         let mut kind = None;
+        let mut arity = None;
 
         for capture in m.captures {
             let capture_name = capture_names
@@ -199,6 +218,10 @@ pub fn parse_tree<'a>(
                 assert!(kind.is_none(), "declare only one kind per match");
                 kind = Some(capture_name)
             }
+
+            if capture_name == "arity" {
+                arity = Some(capture.node.named_child_count());
+            }
         }
 
         match node {
@@ -219,13 +242,19 @@ pub fn parse_tree<'a>(
 
                 let kind = crate::ts_scip::captures_to_kind(&kind);
 
-                let descriptors = descriptors
+                let mut descriptors: Vec<_> = descriptors
                     .iter()
                     .map(|(capture, name)| {
                         crate::ts_scip::capture_name_to_descriptor(capture, name.to_string())
                     })
                     .collect();
 
+                // Functions with the same name but a different number of parameters are
+                // different functions, e.g. greet/1 and greet/2 in Elixir
+                if let (Some(arity), Some(last)) = (arity, descriptors.last_mut()) {
+                    last.disambiguator = arity.to_string();
+                }
+
                 match scope {
                     Some(scope_ident) => scopes.push(Scope {
                         ident_range: node.into(),
@@ -307,6 +336,11 @@ pub fn parse_tree<'a>(
         root.insert_global(m);
     }
 
+    for (config, tree) in injections.parse(source_bytes) {
+        let (embedded, _) = parse_tree(config, &tree, source_bytes)?;
+        root.insert_embedded(embedded);
+    }
+
     Ok((root, globals.len()))
 }
 
@@ -1,11 +1,17 @@
 use std::collections::HashMap;
 
+use anyhow::anyhow;
 use once_cell::sync::OnceCell;
 use regex::Regex;
 use scip::types::Descriptor;
-use scip_macros::include_scip_query;
-use scip_treesitter_languages::parsers::BundledParser;
-use tree_sitter::{Language, Parser, Query};
+use scip_macros::include_project_file_optional;
+use scip_treesitter_languages::{
+    inherits::resolve_inherits,
+    parsers::{BundledParser, ALL_BUNDLED},
+    plugins::plugins,
+    with_bundled_languages,
+};
+use tree_sitter::{Language, Parser, Query, Range, Tree};
 
 #[derive(Debug)]
 pub struct Transform {
@@ -21,7 +27,6 @@ pub struct NodeFilter {
 
 pub struct TagConfiguration {
     language: Language,
-    query_text: String,
     pub tag_query: Query,
     pub sym_query: Query,
 
@@ -30,38 +35,37 @@ pub struct TagConfiguration {
 
     // Handles #filter! predicates in queries
     filters: HashMap<usize, Vec<NodeFilter>>,
+
+    // Handles #set! injection.language in queries, for patterns that capture code in
+    // another language as @injection.content
+    injections: HashMap<usize, String>,
+    injection_content: Option<u32>,
 }
 
 impl TagConfiguration {
     fn new(language: Language, tag_query: &str, sym_query: Option<String>) -> Self {
-        let first_line = tag_query.lines().next();
-        let query_text = match first_line {
-            Some(line) if line.starts_with(";;include") => {
-                let (_, included_lang) =
-                    line.split_once(";;include").expect("must have ;; include");
-                let included_lang = included_lang.trim();
-
-                let parser = BundledParser::get_parser(included_lang).expect("valid language");
-                let configuration = get_tag_configuration(parser).expect("valid config");
-
-                format!("{}\n{}", configuration.query_text, tag_query)
-            }
-            _ => tag_query.to_string(),
-        };
-
-        let query = Query::new(language, &query_text).expect("to parse query");
+        let query = Query::new(language, tag_query).expect("to parse query");
         let sym_query = match sym_query {
             Some(text) => {
-                let text = format!("{query_text}\n{text}");
+                let text = format!("{tag_query}\n{text}");
                 Query::new(language, &text).expect("to parse symbol query")
             }
-            None => Query::new(language, &query_text).expect("to parse query"),
+            None => Query::new(language, tag_query).expect("to parse query"),
         };
 
         let mut transforms = HashMap::new();
         let mut filters = HashMap::new();
+        let mut injections = HashMap::new();
 
         for index in 0..query.pattern_count() {
+            for property in query.property_settings(index) {
+                if let ("injection.language", Some(language)) =
+                    (property.key.as_ref(), property.value.as_deref())
+                {
+                    injections.insert(index, language.to_string());
+                }
+            }
+
             let predicate = query.general_predicates(index);
 
             if !predicate.is_empty() {
@@ -140,11 +144,12 @@ impl TagConfiguration {
 
         Self {
             language,
-            query_text,
+            injection_content: query.capture_index_for_name("injection.content"),
             tag_query: query,
             sym_query,
             transforms,
             filters,
+            injections,
         }
     }
 
@@ -174,6 +179,13 @@ impl TagConfiguration {
         }
     }
 
+    /// The language and range of the code that a match captured as @injection.content.
+    pub fn injection(&self, m: &tree_sitter::QueryMatch) -> Option<(&str, Range)> {
+        let language = self.injections.get(&m.pattern_index)?;
+        let node = m.nodes_for_capture_index(self.injection_content?).next()?;
+        Some((language, node.range()))
+    }
+
     pub fn is_filtered(&self, m: &tree_sitter::QueryMatch) -> bool {
         match self.filters.get(&m.pattern_index) {
             Some(filters) if !filters.is_empty() => filters.iter().any(|filter| {
@@ -198,65 +210,105 @@ impl LocalConfiguration {
     }
 }
 
+// Every bundled language gets the scip-syntax queries found in queries/<name>, and an
+// empty string for the ones it doesn't have. The files are read at compile time and
+// build.rs rebuilds the crate when one is added.
+macro_rules! bundled_queries {
+    (@query $query:ident, $file:tt, $( ($parser:ident, $name:tt) ),*) => {
+        fn $query(lang: &str) -> &'static str {
+            match lang {
+                $( $name => include_project_file_optional!("queries/", $name, $file), )*
+                _ => "",
+            }
+        }
+    };
+    ($( $row:tt ),*) => {
+        bundled_queries!(@query scip_tags, "/scip-tags.scm", $( $row ),*);
+        bundled_queries!(@query scip_references, "/scip-references.scm", $( $row ),*);
+        bundled_queries!(@query scip_locals, "/scip-locals.scm", $( $row ),*);
+    };
+}
+
+with_bundled_languages!(bundled_queries);
+
+/// Resolves the `; inherits:` lines of a bundled query, None if `lang` doesn't have
+/// one. An empty query file counts as missing.
+fn resolve_bundled(lang: &str, query: fn(&str) -> &'static str) -> Option<String> {
+    if query(lang).is_empty() {
+        return None;
+    }
+
+    let resolved = resolve_inherits(lang, |lang| match query(lang) {
+        "" => Err(anyhow!("queries/{lang} doesn't have this query")),
+        text => Ok(text.to_string()),
+    });
+    Some(resolved.unwrap_or_else(|err| panic!("queries of {lang} must resolve: {err:#}")))
+}
+
+// Configurations are created the first time a language is used
+type BundledInstances<T> = OnceCell<HashMap<BundledParser, OnceCell<Option<T>>>>;
+
+fn bundled_instance<T>(
+    instances: &'static BundledInstances<T>,
+    parser: BundledParser,
+    create: impl FnOnce() -> Option<T>,
+) -> Option<&'static T> {
+    instances
+        .get_or_init(|| {
+            ALL_BUNDLED
+                .iter()
+                .map(|&parser| (parser, OnceCell::new()))
+                .collect()
+        })
+        .get(&parser)?
+        .get_or_init(create)
+        .as_ref()
+}
+
 mod tags {
     use super::*;
 
-    macro_rules! create_tags_configuration {
-        ($name:tt, $parser:path, $file:tt) => {
-            pub fn $name() -> &'static TagConfiguration {
-                static INSTANCE: OnceCell<TagConfiguration> = OnceCell::new();
-
-                INSTANCE.get_or_init(|| {
-                    let language = $parser.get_language();
-                    let query = include_scip_query!($file, "scip-tags");
-                    TagConfiguration::new(language, query, None)
-                })
-            }
-        };
-        ($name:tt, $parser:path, $file:tt, $symbol_file:tt) => {
-            pub fn $name() -> &'static TagConfiguration {
-                static INSTANCE: OnceCell<TagConfiguration> = OnceCell::new();
-
-                INSTANCE.get_or_init(|| {
-                    let language = $parser.get_language();
-                    let query = include_scip_query!($file, "scip-tags");
-                    let sym_query = include_scip_query!($file, "scip-references").to_string();
-                    TagConfiguration::new(language, query, Some(sym_query))
-                })
-            }
-        };
+    fn bundled(parser: BundledParser) -> Option<&'static TagConfiguration> {
+        static INSTANCES: BundledInstances<TagConfiguration> = OnceCell::new();
+
+        bundled_instance(&INSTANCES, parser, || {
+            let name = parser.get_language_name();
+            let query = resolve_bundled(name, scip_tags)?;
+            let sym_query = resolve_bundled(name, scip_references);
+            Some(TagConfiguration::new(
+                parser.get_language(),
+                &query,
+                sym_query,
+            ))
+        })
     }
 
-    create_tags_configuration!(c, BundledParser::C, "c");
-    create_tags_configuration!(javascript, BundledParser::Javascript, "javascript");
-    create_tags_configuration!(kotlin, BundledParser::Kotlin, "kotlin");
-    create_tags_configuration!(ruby, BundledParser::Ruby, "ruby");
-    create_tags_configuration!(python, BundledParser::Python, "python");
-    create_tags_configuration!(cpp, BundledParser::Cpp, "cpp");
-    create_tags_configuration!(typescript, BundledParser::Typescript, "typescript");
-    create_tags_configuration!(scala, BundledParser::Scala, "scala");
-    create_tags_configuration!(c_sharp, BundledParser::C_Sharp, "c_sharp");
-    create_tags_configuration!(java, BundledParser::Java, "java");
-    create_tags_configuration!(rust, BundledParser::Rust, "rust");
-    create_tags_configuration!(go, BundledParser::Go, "go", "go");
-    create_tags_configuration!(zig, BundledParser::Zig, "zig");
+    // Queries of plugins were already checked when the plugins were loaded
+    fn plugin(id: usize) -> Option<&'static TagConfiguration> {
+        static INSTANCES: OnceCell<Vec<Option<TagConfiguration>>> = OnceCell::new();
+
+        INSTANCES
+            .get_or_init(|| {
+                plugins()
+                    .iter()
+                    .map(|plugin| {
+                        let query = plugin.queries.scip_tags.as_ref()?;
+                        Some(TagConfiguration::new(plugin.language, query, None))
+                    })
+                    .collect()
+            })
+            .get(id)?
+            .as_ref()
+    }
 
     pub fn get_tag_configuration(parser: BundledParser) -> Option<&'static TagConfiguration> {
+        if !parser.is_enabled() {
+            return None;
+        }
+
         match parser {
-            BundledParser::C => Some(c()),
-            BundledParser::Javascript => Some(javascript()),
-            BundledParser::Kotlin => Some(kotlin()),
-            BundledParser::Ruby => Some(ruby()),
-            BundledParser::Python => Some(python()),
-            BundledParser::Cpp => Some(cpp()),
-            BundledParser::Typescript => Some(typescript()),
-            BundledParser::Scala => Some(scala()),
-            BundledParser::C_Sharp => Some(c_sharp()),
-            BundledParser::Java => Some(java()),
-            BundledParser::Rust => Some(rust()),
-            BundledParser::Go => Some(go()),
-            BundledParser::Zig => Some(zig()),
-            _ => None,
+            BundledParser::Plugin(id) => plugin(id),
+            parser => bundled(parser),
         }
     }
 }
@@ -264,39 +316,145 @@ mod tags {
 mod locals {
     use super::*;
 
-    macro_rules! create_locals_configuration {
-        ($name:tt, $parser:path, $file:tt) => {
-            pub fn $name() -> &'static LocalConfiguration {
-                static INSTANCE: OnceCell<LocalConfiguration> = OnceCell::new();
+    fn bundled(parser: BundledParser) -> Option<&'static LocalConfiguration> {
+        static INSTANCES: BundledInstances<LocalConfiguration> = OnceCell::new();
 
-                INSTANCE.get_or_init(|| {
-                    let language = $parser.get_language();
-                    let query = include_scip_query!($file, "scip-locals");
+        bundled_instance(&INSTANCES, parser, || {
+            let language = parser.get_language();
+            let query = resolve_bundled(parser.get_language_name(), scip_locals)?;
 
-                    LocalConfiguration {
-                        language,
-                        query: Query::new(language, query).unwrap(),
-                    }
-                })
-            }
-        };
+            Some(LocalConfiguration {
+                language,
+                query: Query::new(language, &query).unwrap(),
+            })
+        })
     }
 
-    create_locals_configuration!(go, BundledParser::Go, "go");
-    create_locals_configuration!(perl, BundledParser::Perl, "perl");
-    create_locals_configuration!(matlab, BundledParser::Matlab, "matlab");
-    create_locals_configuration!(java, BundledParser::Java, "java");
+    fn plugin(id: usize) -> Option<&'static LocalConfiguration> {
+        static INSTANCES: OnceCell<Vec<Option<LocalConfiguration>>> = OnceCell::new();
+
+        INSTANCES
+            .get_or_init(|| {
+                plugins()
+                    .iter()
+                    .map(|plugin| {
+                        let query = plugin.queries.scip_locals.as_ref()?;
+                        // Compiled when the plugin was loaded as well, a plugin whose query
+                        // fails here is left without locals instead of taking us down
+                        match Query::new(plugin.language, query) {
+                            Ok(query) => Some(LocalConfiguration {
+                                language: plugin.language,
+                                query,
+                            }),
+                            Err(err) => {
+                                eprintln!(
+                                    "scip-locals.scm of plugin {} doesn't compile: {err}",
+                                    plugin.name
+                                );
+                                None
+                            }
+                        }
+                    })
+                    .collect()
+            })
+            .get(id)?
+            .as_ref()
+    }
 
     pub fn get_local_configuration(parser: BundledParser) -> Option<&'static LocalConfiguration> {
+        if !parser.is_enabled() {
+            return None;
+        }
+
         match parser {
-            BundledParser::Go => Some(go()),
-            BundledParser::Perl => Some(perl()),
-            BundledParser::Matlab => Some(matlab()),
-            BundledParser::Java => Some(java()),
-            _ => None,
+            BundledParser::Plugin(id) => plugin(id),
+            parser => bundled(parser),
         }
     }
 }
 
 pub use locals::get_local_configuration;
 pub use tags::get_tag_configuration;
+
+/// The parts of a document that are in other languages, like the `<script>` of a Vue
+/// component, grouped by language.
+#[derive(Default)]
+pub struct Injections<'a> {
+    languages: Vec<(&'a str, Vec<Range>)>,
+}
+
+impl<'a> Injections<'a> {
+    pub fn add(&mut self, language: &'a str, range: Range) {
+        match self
+            .languages
+            .iter_mut()
+            .find(|(name, _)| *name == language)
+        {
+            Some((_, ranges)) => ranges.push(range),
+            None => self.languages.push((language, vec![range])),
+        }
+    }
+
+    /// Parses the parts of every language that has tags, as if the rest of the document
+    /// wasn't there. Positions in the trees are those in the whole document.
+    pub fn parse(self, source_bytes: &[u8]) -> Vec<(&'static TagConfiguration, Tree)> {
+        self.languages
+            .into_iter()
+            .filter_map(|(language, mut ranges)| {
+                let config = get_tag_configuration(BundledParser::get_parser(language)?)?;
+
+                // Not a pooled parser, the included ranges would stay set on it
+                ranges.sort_by_key(|range| range.start_byte);
+                let mut parser = config.get_parser();
+                parser.set_included_ranges(&ranges).ok()?;
+                Some((config, parser.parse(source_bytes, None)?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use scip::types::symbol_information;
+    use scip_treesitter_languages::validation::{validate_query_directory, QueryKind};
+
+    use super::*;
+    use crate::ts_scip::captures_to_kind;
+
+    #[test]
+    fn test_bundled_queries() {
+        let queries: PathBuf = [env!("CARGO_MANIFEST_DIR"), "queries"].iter().collect();
+
+        let problems = validate_query_directory(&queries, |parser, kind, query| {
+            let mut problems = vec![];
+
+            // Every query file must be picked up, see bundled_queries!
+            let used = match kind {
+                QueryKind::ScipTags => get_tag_configuration(parser).is_some(),
+                QueryKind::ScipReferences => get_tag_configuration(parser).map_or(false, |tags| {
+                    tags.sym_query.pattern_count() > tags.tag_query.pattern_count()
+                }),
+                QueryKind::ScipLocals => get_local_configuration(parser).is_some(),
+                _ => true,
+            };
+            if !used {
+                problems.push("is not used".to_string());
+            }
+
+            for capture in query.capture_names() {
+                if capture.starts_with("kind")
+                    && captures_to_kind(&Some(capture)) == symbol_information::Kind::UnspecifiedKind
+                {
+                    problems.push(format!("@{capture} is not a known kind"));
+                }
+            }
+
+            problems
+        })
+        .unwrap();
+
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+    }
+}
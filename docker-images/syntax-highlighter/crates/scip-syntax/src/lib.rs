@@ -1,8 +1,11 @@
 use anyhow::Result;
 use scip::types::Occurrence;
-use scip_treesitter_languages::parsers::BundledParser;
+use scip_treesitter::diagnostics::{syntax_errors, SyntaxError};
+use scip_treesitter_languages::{parsers::BundledParser, pool};
+use tree_sitter::Tree;
 
 pub mod ctags;
+pub mod encoding;
 pub mod globals;
 pub mod languages;
 pub mod locals;
@@ -10,13 +13,23 @@ pub mod symbols;
 pub mod ts_scip;
 
 pub fn get_symbols(parser: BundledParser, source_bytes: &[u8]) -> Result<scip::types::Document> {
+    let tree = pool::parser(parser)
+        .parse(source_bytes, None)
+        .expect("to parse tree");
+    get_symbols_for_tree(parser, &tree, source_bytes)
+}
+
+/// Like [`get_symbols`], for callers that already parsed the source.
+pub fn get_symbols_for_tree(
+    parser: BundledParser,
+    tree: &Tree,
+    source_bytes: &[u8],
+) -> Result<scip::types::Document> {
     let config = match crate::languages::get_tag_configuration(parser) {
         Some(config) => config,
         None => return Err(anyhow::anyhow!("Missing config for language")),
     };
-    let mut parser = config.get_parser();
-    let tree = parser.parse(source_bytes, None).expect("to parse tree");
-    let (mut symbol_scope, hint) = symbols::parse_tree(config, &tree, source_bytes)?;
+    let (mut symbol_scope, hint) = symbols::parse_tree(config, tree, source_bytes)?;
     let document = symbol_scope.into_document(hint, vec![]);
     Ok(document)
 }
@@ -26,21 +39,38 @@ pub fn get_globals(
     source_bytes: &[u8],
 ) -> Option<Result<(globals::Scope, usize)>> {
     let config = languages::get_tag_configuration(parser)?;
-    let mut parser = config.get_parser();
-    let tree = parser.parse(source_bytes, None).unwrap();
+    let tree = pool::parser(parser).parse(source_bytes, None).unwrap();
     Some(globals::parse_tree(config, &tree, source_bytes))
 }
 
 pub fn get_locals(parser: BundledParser, source_bytes: &[u8]) -> Option<Result<Vec<Occurrence>>> {
+    let tree = pool::parser(parser).parse(source_bytes, None).unwrap();
+    get_locals_for_tree(parser, &tree, source_bytes)
+}
+
+/// Like [`get_locals`], for callers that already parsed the source.
+pub fn get_locals_for_tree(
+    parser: BundledParser,
+    tree: &Tree,
+    source_bytes: &[u8],
+) -> Option<Result<Vec<Occurrence>>> {
     let config = languages::get_local_configuration(parser)?;
-    let mut parser = config.get_parser();
-    let tree = parser.parse(source_bytes, None).unwrap();
-    Some(locals::parse_tree(config, &tree, source_bytes))
+    Some(locals::parse_tree(config, tree, source_bytes))
+}
+
+/// Returns one occurrence for every syntax error in the tree, with the error
+/// attached as a diagnostic. Only needs a parsed tree, so it also works for
+/// languages without tag or locals queries.
+pub fn get_diagnostics(tree: &Tree, source_bytes: &[u8]) -> Vec<Occurrence> {
+    syntax_errors(tree.root_node(), source_bytes)
+        .iter()
+        .map(SyntaxError::to_occurrence)
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use std::{io::BufWriter, path::Path};
+    use std::io::BufWriter;
 
     use scip_treesitter::snapshot::dump_document;
     use scip_treesitter_languages::parsers::BundledParser;
@@ -56,13 +86,8 @@ mod test {
 
                 let source_code = include_str!(concat!("../testdata/", $filename));
 
-                let extension = Path::new(&filename)
-                    .extension()
-                    .expect("to have extension")
-                    .to_str()
-                    .expect("to have valid utf8 string");
                 let parser =
-                    BundledParser::get_parser_from_extension(extension).expect("to have parser");
+                    BundledParser::get_parser_from_file_name(filename).expect("to have parser");
                 let config =
                     crate::languages::get_tag_configuration(parser).expect("to have rust parser");
                 let doc = crate::globals::test::parse_file_for_lang(config, &source_code)
@@ -125,4 +150,82 @@ mod test {
         test_scip_tags_go_constant,
         "go-const.go"
     );
+
+    // Configuration files, where the symbols are key paths
+    generate_tags_and_snapshot!(Scip, test_scip_json, "config.json");
+    generate_tags_and_snapshot!(Scip, test_scip_yaml, "deployment.yaml");
+    generate_tags_and_snapshot!(Scip, test_scip_toml, "config.toml");
+    generate_tags_and_snapshot!(Scip, test_scip_hcl, "main.tf");
+
+    // Scripts and styles are tagged as their own language
+    generate_tags_and_snapshot!(Scip, test_scip_css, "styles.css");
+    generate_tags_and_snapshot!(Scip, test_scip_html, "page.html");
+    generate_tags_and_snapshot!(Scip, test_scip_vue, "Counter.vue");
+    generate_tags_and_snapshot!(Scip, test_scip_svelte, "Greeting.svelte");
+
+    // Scripts and build files, some of which are only known by their file name
+    generate_tags_and_snapshot!(All, test_tags_bash, test_scip_bash, "build.sh");
+    generate_tags_and_snapshot!(Scip, test_scip_make, "Makefile");
+    generate_tags_and_snapshot!(Scip, test_scip_cmake, "CMakeLists.txt");
+    generate_tags_and_snapshot!(Scip, test_scip_starlark, "go_proto.bzl");
+
+    // Members of extensions, categories and tables are members of the type they extend
+    generate_tags_and_snapshot!(Scip, test_scip_swift, "Shapes.swift");
+    generate_tags_and_snapshot!(Scip, test_scip_objc, "Shapes.mm");
+    generate_tags_and_snapshot!(Scip, test_scip_dart, "shapes.dart");
+    generate_tags_and_snapshot!(Scip, test_scip_lua, "shapes.lua");
+
+    // Functional languages, where functions may be told apart by their arity
+    generate_tags_and_snapshot!(Scip, test_scip_haskell, "Shapes.hs");
+    generate_tags_and_snapshot!(Scip, test_scip_ocaml, "shapes.ml");
+    generate_tags_and_snapshot!(Scip, test_scip_elixir, "greeter.ex");
+    generate_tags_and_snapshot!(Scip, test_scip_erlang, "greeter.erl");
+    generate_tags_and_snapshot!(Scip, test_scip_clojure, "shapes.clj");
+
+    // Schemas and interface definitions, whose messages, types and services are nested
+    generate_tags_and_snapshot!(All, test_tags_protobuf, test_scip_protobuf, "shapes.proto");
+    generate_tags_and_snapshot!(Scip, test_scip_graphql, "schema.graphql");
+    generate_tags_and_snapshot!(Scip, test_scip_thrift, "shapes.thrift");
+
+    fn diagnostics_for_go(source: &str) -> Vec<scip::types::Occurrence> {
+        let tree = scip_treesitter_languages::pool::parser(BundledParser::Go)
+            .parse(source, None)
+            .expect("to parse");
+        crate::get_diagnostics(&tree, source.as_bytes())
+    }
+
+    #[test]
+    fn test_diagnostics_for_valid_source() {
+        let diagnostics = diagnostics_for_go("package main\n\nfunc main() {}\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics_for_broken_source() {
+        let diagnostics = diagnostics_for_go("package main\n\nfunc main() {\n\tx := \n");
+        assert!(!diagnostics.is_empty());
+
+        for occ in diagnostics {
+            assert!(occ.symbol.is_empty());
+            assert_eq!(occ.diagnostics.len(), 1);
+
+            let diagnostic = &occ.diagnostics[0];
+            assert_eq!(diagnostic.severity, scip::types::Severity::Error.into());
+            assert_eq!(diagnostic.source, "tree-sitter");
+            assert!(!diagnostic.message.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_name_the_expected_node() {
+        let diagnostics = diagnostics_for_go("package main\n\nfunc f() int {\n\treturn ) 1\n}\n");
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|occ| occ.diagnostics[0].message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![r#"unexpected ")" in return_statement, expected expression_list"#]
+        );
+    }
 }
@@ -6,6 +6,7 @@ use scip::{
     types::{Occurrence, Symbol},
 };
 use scip_treesitter::{prelude::*, types::PackedRange};
+use scip_treesitter_languages::pool;
 use tree_sitter::Node;
 
 use crate::languages::LocalConfiguration;
@@ -74,7 +75,17 @@ impl<'a> Scope<'a> {
             .iter_mut()
             .find(|child| child.range.contains(&lvalue.range))
         {
-            child.insert_lvalue(lvalue)
+            // "parent" definitions belong to the scope around the innermost one, e.g.
+            // the name of a local function, which is within the function's own scope
+            let innermost = !child
+                .children
+                .iter()
+                .any(|grandchild| grandchild.range.contains(&lvalue.range));
+            if lvalue.scope_modifier == ScopeModifier::Parent && innermost {
+                self.lvalues.insert(lvalue.identifier, lvalue);
+            } else {
+                child.insert_lvalue(lvalue)
+            }
         } else {
             self.lvalues.insert(lvalue.identifier, lvalue);
         }
@@ -348,7 +359,7 @@ pub fn parse_tree<'a>(
     tree: &'a tree_sitter::Tree,
     source_bytes: &'a [u8],
 ) -> Result<Vec<scip::types::Occurrence>> {
-    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut cursor = pool::query_cursor();
 
     let root_node = tree.root_node();
     let capture_names = config.query.capture_names();
@@ -609,4 +620,16 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_can_do_lua() -> Result<()> {
+        let config = crate::languages::get_local_configuration(BundledParser::Lua).unwrap();
+        let source_code = include_str!("../testdata/locals.lua");
+        let doc = parse_file_for_lang(config, source_code)?;
+
+        let dumped = snapshot_syntax_document(&doc, source_code);
+        insta::assert_snapshot!(dumped);
+
+        Ok(())
+    }
 }
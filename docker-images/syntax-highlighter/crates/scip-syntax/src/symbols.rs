@@ -2,8 +2,9 @@ use anyhow::Result;
 use protobuf::Enum;
 use scip::types::{symbol_information, Descriptor, Document, Occurrence, SymbolInformation};
 use scip_treesitter::types::PackedRange;
+use scip_treesitter_languages::pool;
 
-use crate::languages::TagConfiguration;
+use crate::languages::{Injections, TagConfiguration};
 
 #[derive(Debug)]
 pub struct Scope {
@@ -68,6 +69,21 @@ impl Scope {
         }
     }
 
+    // The symbols of code in another language that is part of this document
+    fn insert_embedded(&mut self, embedded: Scope) {
+        for scope in embedded.children {
+            self.insert_scope(scope);
+        }
+
+        for global in embedded.globals {
+            self.insert_global(global);
+        }
+
+        for reference in embedded.referrences {
+            self.insert_reference(reference);
+        }
+    }
+
     pub fn into_document(&mut self, hint: usize, base_descriptors: Vec<Descriptor>) -> Document {
         let mut descriptor_stack = base_descriptors;
 
@@ -174,7 +190,7 @@ pub fn parse_tree<'a>(
     tree: &'a tree_sitter::Tree,
     source_bytes: &'a [u8],
 ) -> Result<(Scope, usize)> {
-    let mut cursor = tree_sitter::QueryCursor::new();
+    let mut cursor = pool::query_cursor();
 
     let root_node = tree.root_node();
     let capture_names = config.sym_query.capture_names();
@@ -182,6 +198,7 @@ pub fn parse_tree<'a>(
     let mut scopes = vec![];
     let mut globals = vec![];
     let mut references = vec![];
+    let mut injections = Injections::default();
 
     let matches = cursor.matches(&config.sym_query, root_node, source_bytes);
     for m in matches {
@@ -189,6 +206,11 @@ pub fn parse_tree<'a>(
             continue;
         }
 
+        if let Some((language, range)) = config.injection(&m) {
+            injections.add(language, range);
+            continue;
+        }
+
         let mut node = None;
         let mut enclosing_node = None;
         let mut scope = None;
@@ -327,6 +349,11 @@ pub fn parse_tree<'a>(
         root.insert_reference(reference)
     }
 
+    for (config, tree) in injections.parse(source_bytes) {
+        let (embedded, _) = parse_tree(config, &tree, source_bytes)?;
+        root.insert_embedded(embedded);
+    }
+
     Ok((root, globals.len()))
 }
 
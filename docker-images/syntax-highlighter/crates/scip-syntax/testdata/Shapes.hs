@@ -0,0 +1,26 @@
+module Shapes where
+
+data Shape = Circle Double | Square Double
+
+data Point = Point { x :: Double, y :: Double }
+
+newtype Name = Name String
+
+type Radius = Double
+
+class HasArea a where
+  area :: a -> Double
+
+instance HasArea Shape where
+  area (Circle r) = pi * r * r
+  area (Square s) = s * s
+
+origin :: Point
+origin = Point 0 0
+
+scale :: Double -> Shape -> Shape
+scale k (Circle r) = Circle (k * r)
+scale k (Square s) = Square (k * s)
+
+describe (Circle _) = "circle"
+describe (Square _) = "square"
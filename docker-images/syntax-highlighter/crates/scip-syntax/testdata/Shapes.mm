@@ -0,0 +1,32 @@
+#import <Foundation/Foundation.h>
+
+@protocol Drawable
+- (void)draw;
+@end
+
+@interface Shape : NSObject <Drawable>
+- (double)area;
+@end
+
+@interface Shape (Scaling)
+- (Shape *)scaledBy:(double)factor;
+@end
+
+@implementation Shape
+- (double)area {
+  return 0;
+}
+
+- (void)draw {
+}
+@end
+
+@implementation Shape (Scaling)
+- (Shape *)scaledBy:(double)factor {
+  return self;
+}
+@end
+
+static int ShapeCount(void) {
+  return 0;
+}
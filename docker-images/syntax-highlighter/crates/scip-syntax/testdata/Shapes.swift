@@ -0,0 +1,45 @@
+import Foundation
+
+let defaultSides = 4
+
+protocol Shape {
+    var area: Double { get }
+    func describe() -> String
+}
+
+struct Square: Shape {
+    let side: Double
+    var area: Double { side * side }
+
+    func describe() -> String {
+        let name = "square"
+        return "\(name) with side \(side)"
+    }
+}
+
+final class Canvas {
+    private var shapes: [Shape] = []
+
+    init() {}
+
+    func add(_ shape: Shape) {
+        shapes.append(shape)
+    }
+}
+
+enum Color {
+    case red, green
+    case custom(hex: String)
+}
+
+extension Square {
+    static func unit() -> Square {
+        Square(side: 1)
+    }
+}
+
+typealias Shapes = [Shape]
+
+func totalArea(of shapes: Shapes) -> Double {
+    shapes.reduce(0) { $0 + $1.area }
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+readonly VERSION="1.2.0"
+export BUILD_DIR="${BUILD_DIR:-./build}"
+
+log() {
+  echo "[$(date +%T)] $*" >&2
+}
+
+function build {
+  local target=$1
+  mkdir -p "$BUILD_DIR"
+  go build -o "$BUILD_DIR/$target" ./cmd/"$target"
+}
+
+for t in server client; do
+  build "$t" && log "built $t"
+done
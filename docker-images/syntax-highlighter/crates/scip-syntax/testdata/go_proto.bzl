@@ -0,0 +1,23 @@
+"""Rules and macros for building protos."""
+
+load("@rules_proto//proto:defs.bzl", "ProtoInfo")
+
+DEFAULT_VISIBILITY = ["//visibility:public"]
+
+GoProtoInfo = provider(fields = ["srcs", "deps"])
+
+def _go_proto_impl(ctx):
+    srcs = [f for f in ctx.files.srcs if f.extension == "proto"]
+    return [GoProtoInfo(srcs = srcs, deps = ctx.attr.deps)]
+
+go_proto = rule(
+    implementation = _go_proto_impl,
+    attrs = {
+        "srcs": attr.label_list(allow_files = True),
+        "deps": attr.label_list(),
+    },
+)
+
+def go_proto_library(name, srcs, deps = [], **kwargs):
+    go_proto(name = name + "_gen", srcs = srcs, deps = deps)
+    native.filegroup(name = name, srcs = [":" + name + "_gen"], **kwargs)
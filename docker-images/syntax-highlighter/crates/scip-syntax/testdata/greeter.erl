@@ -0,0 +1,17 @@
+-module(greeter).
+-export([greet/1, greet/2]).
+
+-record(person, {name, title = "Dr."}).
+
+-type greeting() :: string().
+
+greet(Name) ->
+    greet(Name, "Hello").
+
+greet(#person{name = Name}, Greeting) ->
+    Greeting ++ " " ++ Name;
+greet(Name, Greeting) ->
+    Helper = fun(X) -> X end,
+    Helper(Greeting ++ " " ++ Name).
+
+version() -> 1.
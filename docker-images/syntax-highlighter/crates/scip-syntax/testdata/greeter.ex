@@ -0,0 +1,26 @@
+defmodule Greeter.Formal do
+  @moduledoc "Greets people"
+
+  defstruct [:name, title: "Dr."]
+
+  def greet(name), do: "Hello " <> name
+  def greet(name, title) when is_binary(title), do: title <> name
+
+  defp format(x \\ "") do
+    x
+  end
+
+  def version, do: 1
+
+  defmacro debug(expr) do
+    quote do: IO.inspect(unquote(expr))
+  end
+
+  defprotocol Shape do
+    def area(shape)
+  end
+end
+
+defimpl Greeter.Shape, for: Circle do
+  def area(c), do: c.r * c.r
+end
@@ -0,0 +1,13 @@
+local count = 0
+
+local function increment(step)
+  count = count + step
+  return count
+end
+
+for i = 1, 3 do
+  local doubled = i * 2
+  increment(doubled)
+end
+
+print(count)
@@ -0,0 +1,18 @@
+terraform {
+  required_version = ">= 1.0"
+}
+
+variable "region" {
+  default = "us-east-1"
+}
+
+resource "aws_s3_bucket" "logs" {
+  bucket = "my-logs"
+  tags = {
+    Name = "logs"
+  }
+
+  lifecycle {
+    prevent_destroy = true
+  }
+}
@@ -0,0 +1,25 @@
+(ns my.app.shapes
+  (:require [clojure.string :as str]))
+
+(def pi 3.14159)
+
+(defprotocol Shape
+  "Something with an area"
+  (area [this])
+  (describe [this prefix]))
+
+(defrecord Circle [radius]
+  Shape
+  (area [_] (* pi radius radius))
+  (describe [this prefix] (str prefix " circle")))
+
+(defn- square [x] (* x x))
+
+(defn scale
+  [k shape]
+  (let [helper (fn [r] (* k r))]
+    (def inner 1)
+    (->Circle (helper (:radius shape)))))
+
+(defmacro unless [test & body]
+  `(if ~test nil (do ~@body)))
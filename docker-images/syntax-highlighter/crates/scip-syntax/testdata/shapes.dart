@@ -0,0 +1,33 @@
+import 'dart:math';
+
+abstract class Shape {
+  double area();
+
+  String describe() {
+    final name = runtimeType.toString();
+    return '$name with area ${area()}';
+  }
+}
+
+class Circle extends Shape {
+  final double radius;
+
+  Circle(this.radius);
+
+  @override
+  double area() => pi * radius * radius;
+}
+
+mixin Named {
+  String get name => 'shape';
+}
+
+extension Scaling on Circle {
+  Circle scaled(double factor) => Circle(radius * factor);
+}
+
+enum Color { red, green }
+
+double totalArea(List<Shape> shapes) {
+  return shapes.fold(0, (sum, shape) => sum + shape.area());
+}
@@ -0,0 +1,21 @@
+local Shape = {}
+Shape.__index = Shape
+
+VERSION = "1.0"
+
+function Shape.new(width, height)
+  local self = setmetatable({}, Shape)
+  self.width = width
+  self.height = height
+  return self
+end
+
+function Shape:area()
+  return self.width * self.height
+end
+
+local function clamp(value, low, high)
+  return math.max(low, math.min(value, high))
+end
+
+return Shape
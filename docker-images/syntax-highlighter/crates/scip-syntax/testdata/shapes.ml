@@ -0,0 +1,23 @@
+module type SHAPE = sig
+  val area : float -> float
+end
+
+type point = { x : float; y : float }
+
+type shape = Circle of float | Square of float
+
+module Circle = struct
+  let pi = 3.14159
+
+  let area r =
+    let square = r *. r in
+    pi *. square
+end
+
+let origin = { x = 0.0; y = 0.0 }
+
+let scale k = function
+  | Circle r -> Circle (k *. r)
+  | Square s -> Square (k *. s)
+
+external sqrt : float -> float = "caml_sqrt_float"
@@ -0,0 +1,28 @@
+namespace go acme.shapes
+
+const i32 MAX_SHAPES = 100
+
+typedef string ShapeId
+
+enum Color {
+  RED = 1,
+  GREEN = 2
+}
+
+struct Circle {
+  1: required ShapeId id,
+  2: optional double radius
+}
+
+union Shape {
+  1: Circle circle
+}
+
+exception NotFound {
+  1: string message
+}
+
+service ShapeService {
+  Circle getCircle(1: ShapeId id) throws (1: NotFound notFound),
+  oneway void ping()
+}
@@ -3,8 +3,8 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::ValueEnum;
 use scip::{types::Document, write_message_to_file};
-use scip_syntax::{get_locals, get_symbols};
-use scip_treesitter_languages::parsers::BundledParser;
+use scip_syntax::{get_diagnostics, get_locals_for_tree, get_symbols_for_tree};
+use scip_treesitter_languages::{parsers::BundledParser, pool};
 use walkdir::DirEntry;
 
 use crate::{
@@ -19,6 +19,9 @@ pub struct IndexOptions {
     /// Otherwise errors are logged but they don't
     /// interrupt the process
     pub fail_fast: bool,
+    /// When true, syntax errors are attached to the
+    /// documents as diagnostics
+    pub diagnostics: bool,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -118,19 +121,14 @@ pub fn index_command(
             bar.finish();
         }
         IndexMode::Workspace { location } => {
-            let extensions = BundledParser::get_language_extensions(&p);
+            // By whole file name first, so that e.g. Dockerfile is found too
             let is_valid = |entry: &DirEntry| {
                 entry.file_type().is_dir()
                     || entry
                         .file_name()
                         .to_str()
-                        .map(|s| {
-                            s.split('.')
-                                .last()
-                                .filter(|ext| extensions.contains(ext))
-                                .is_some()
-                        })
-                        .unwrap_or(false)
+                        .and_then(BundledParser::get_parser_from_file_name)
+                        == Some(p)
             };
 
             let bar = create_spinner();
@@ -179,14 +177,18 @@ fn index_content(
 ) -> Result<Document> {
     let mut document: Document;
 
+    let tree = pool::parser(parser)
+        .parse(&contents, None)
+        .ok_or_else(|| anyhow::anyhow!("Failed to parse tree"))?;
+
     if options.analysis_mode.globals() {
-        document = get_symbols(parser, &contents).unwrap();
+        document = get_symbols_for_tree(parser, &tree, &contents).unwrap();
     } else {
         document = Document::new();
     }
 
     if options.analysis_mode.locals() {
-        let locals = get_locals(parser, &contents);
+        let locals = get_locals_for_tree(parser, &tree, &contents);
 
         match locals {
             Some(Ok(occurrences)) => {
@@ -200,5 +202,11 @@ fn index_content(
         }
     }
 
+    if options.diagnostics {
+        document
+            .occurrences
+            .extend(get_diagnostics(&tree, &contents));
+    }
+
     Ok(document)
 }
@@ -50,6 +50,10 @@ enum Commands {
         /// Evaluate the build index against an index from a file
         #[arg(long)]
         evaluate: Option<String>,
+
+        /// Attach syntax errors to the index as diagnostics
+        #[arg(long, default_value_t = false)]
+        diagnostics: bool,
     },
 
     /// Fuzzily evaluate candidate SCIP index against known ground truth
@@ -93,6 +97,7 @@ pub fn main() {
             fail_fast,
             project_root,
             evaluate,
+            diagnostics,
         } => {
             let index_mode = {
                 match workspace {
@@ -118,6 +123,7 @@ pub fn main() {
                 IndexOptions {
                     analysis_mode: mode,
                     fail_fast,
+                    diagnostics,
                 },
             )
         }
@@ -0,0 +1,5 @@
+fn main() {
+    // Query files are included by proc macros, which can't tell cargo about files that
+    // don't exist yet. Rebuild when a query is added, so it is picked up.
+    println!("cargo:rerun-if-changed=queries");
+}
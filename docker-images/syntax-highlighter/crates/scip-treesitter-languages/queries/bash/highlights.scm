@@ -0,0 +1,84 @@
+(comment) @comment
+
+[
+  (string)
+  (raw_string)
+  (heredoc_body)
+  (heredoc_start)
+  (ansi_c_string)
+] @string
+
+(regex) @string.special
+
+(function_definition name: (word) @identifier.function)
+
+((command_name (word) @function.builtin)
+ (#match? @function.builtin "^(cd|echo|eval|exec|exit|printf|read|return|set|shift|source|trap|true|false)$"))
+(command_name (word) @identifier.function)
+
+(variable_name) @variable
+(special_variable_name) @variable.builtin
+
+(command argument: (word) @variable.parameter
+  (#match? @variable.parameter "^-"))
+
+(file_descriptor) @number
+
+[
+  "case"
+  "do"
+  "done"
+  "elif"
+  "else"
+  "esac"
+  "export"
+  "fi"
+  "for"
+  "function"
+  "if"
+  "in"
+  "local"
+  "declare"
+  "readonly"
+  "typeset"
+  "unset"
+  "unsetenv"
+  "then"
+  "while"
+] @keyword
+
+[
+  "$"
+  "&&"
+  "||"
+  "|"
+  "|&"
+  ">"
+  ">>"
+  "<"
+  "&>"
+  "&>>"
+  "<<"
+  "<<<"
+  "="
+  "=="
+  "!="
+  "=~"
+] @operator
+
+(expansion
+  [
+    "${"
+    "}"
+  ] @punctuation.bracket)
+
+(command_substitution
+  [
+    "$("
+    ")"
+  ] @punctuation.bracket)
+
+[
+  ";"
+  ";;"
+] @punctuation.delimiter
@@ -0,0 +1,36 @@
+[
+  (comment)
+  (dis_expr)
+] @comment
+
+[
+  (str_lit)
+  (char_lit)
+] @string
+(regex_lit) @string.special
+
+(num_lit) @number
+(bool_lit) @boolean
+(nil_lit) @constant.null
+(kwd_lit) @constant
+
+;; Special forms and the def macros, which are the first symbol of a list
+((list_lit . (sym_lit) @keyword)
+ (#match? @keyword "^(def|defn|defn-|defmacro|defmulti|defmethod|defprotocol|defrecord|deftype|ns|fn|let|if|when|cond|do|loop|recur|try|catch|finally|throw)$"))
+
+((list_lit . (sym_lit) @_def . (sym_lit) @identifier.function)
+ (#match? @_def "^(defn|defn-|defmacro|defmulti)$"))
+
+;; Anything else at the start of a list is called
+(list_lit . (sym_lit) @identifier.function)
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+(sym_lit) @variable
@@ -0,0 +1,41 @@
+[
+  (bracket_comment)
+  (line_comment)
+] @comment
+
+[
+  (quoted_argument)
+  (bracket_argument)
+] @string
+
+(variable_ref) @variable
+
+(function_def
+  (function_command
+    (argument_list . (argument) @identifier.function)))
+
+(macro_def
+  (macro_command
+    (argument_list . (argument) @identifier.function)))
+
+(normal_command (identifier) @identifier.function)
+
+[
+  (function)
+  (endfunction)
+  (macro)
+  (endmacro)
+  (if)
+  (elseif)
+  (else)
+  (endif)
+  (foreach)
+  (endforeach)
+  (while)
+  (endwhile)
+] @keyword
+
+[
+  "("
+  ")"
+] @punctuation.bracket
@@ -20,7 +20,7 @@
     (field_identifier)
   ] @identifier.function)
 
-(destructor_name (identifier) @skip) @identifier.function
+(destructor_name (identifier) @_skip) @identifier.function
 (preproc_function_def
   name: (identifier) @identifier.function)
 
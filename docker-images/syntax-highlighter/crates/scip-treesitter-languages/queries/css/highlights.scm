@@ -0,0 +1,103 @@
+(comment) @comment
+
+; Selectors
+
+[
+  (tag_name)
+  (nesting_selector)
+  (universal_selector)]
+@tag
+
+(attribute_selector (plain_value) @string)
+(pseudo_element_selector (tag_name) @identifier.attribute)
+(pseudo_class_selector (class_name) @identifier.attribute)
+
+[
+  (class_name)
+  (id_name)
+  (attribute_name)]
+@tag.attribute
+
+(namespace_name) @identifier.module
+
+; Properties, custom properties first so that they win
+
+((property_name) @variable
+ (#match? @variable "^--"))
+((plain_value) @variable
+ (#match? @variable "^--"))
+
+[
+  (property_name)
+  (feature_name)]
+@property
+
+(function_name) @identifier.function
+
+; Keywords
+
+[
+  "@media"
+  "@import"
+  "@charset"
+  "@namespace"
+  "@supports"
+  "@keyframes"
+  (at_keyword)
+  (to)
+  (from)
+  (important)]
+@keyword
+
+(keyframes_name) @identifier.constant
+
+; Literals
+
+(string_value) @string
+(color_value) @string.special
+
+[
+  (integer_value)
+  (float_value)]
+@number
+
+(unit) @type
+
+; Operators and punctuation
+
+[
+  "~"
+  ">"
+  "+"
+  "-"
+  "*"
+  "/"
+  "="
+  "^="
+  "|="
+  "~="
+  "$="
+  "*="
+  "and"
+  "or"
+  "not"
+  "only"]
+@operator
+
+[
+  "#"
+  ","
+  "."
+  ":"
+  "::"
+  ";"]
+@punctuation.delimiter
+
+[
+  "{"
+  "}"
+  "("
+  ")"
+  "["
+  "]"]
+@punctuation.bracket
@@ -0,0 +1,70 @@
+[
+  (comment)
+  (documentation_comment)
+] @comment
+
+(string_literal) @string
+(symbol_literal) @string.special
+
+[
+  (hex_integer_literal)
+  (decimal_integer_literal)
+  (decimal_floating_point_literal)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+(null_literal) @constant.null
+
+(class_definition name: (identifier) @type)
+(enum_declaration name: (identifier) @type)
+(enum_constant name: (identifier) @constant)
+(type_identifier) @type
+
+(function_signature name: (identifier) @identifier.function)
+
+(this) @variable.builtin
+
+[
+  (const_builtin)
+  (final_builtin)
+] @keyword
+
+[
+  "class"
+  "do"
+  "else"
+  "enum"
+  "extends"
+  "extension"
+  "for"
+  "if"
+  "import"
+  "in"
+  "is"
+  "new"
+  "on"
+  "return"
+  "switch"
+  "while"
+  "with"
+] @keyword
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ";"
+  "."
+  ","
+] @punctuation.delimiter
+
+(identifier) @variable
@@ -0,0 +1,51 @@
+(comment) @comment
+
+[
+  "FROM"
+  "AS"
+  "RUN"
+  "CMD"
+  "LABEL"
+  "EXPOSE"
+  "ENV"
+  "ADD"
+  "COPY"
+  "ENTRYPOINT"
+  "VOLUME"
+  "USER"
+  "WORKDIR"
+  "ARG"
+  "ONBUILD"
+  "STOPSIGNAL"
+  "HEALTHCHECK"
+  "SHELL"
+  "MAINTAINER"
+] @keyword
+
+[
+  (double_quoted_string)
+  (single_quoted_string)
+  (json_string)
+] @string
+
+(image_spec name: (image_name) @identifier.type)
+(image_alias) @identifier.type
+
+(env_pair name: (unquoted_string) @variable)
+(arg_instruction name: (unquoted_string) @variable)
+(expansion (variable) @variable)
+
+(param) @variable.parameter
+
+[
+  ":"
+  "@"
+  "="
+] @operator
+
+(expansion
+  [
+    "$"
+    "{"
+    "}"
+  ] @punctuation.bracket)
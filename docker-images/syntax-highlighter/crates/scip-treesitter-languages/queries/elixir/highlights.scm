@@ -0,0 +1,89 @@
+(comment) @comment
+
+;; @moduledoc and @doc strings are documentation
+(unary_operator
+  operator: "@"
+  operand: (call
+    target: (identifier) @_attribute
+    (arguments [(string) (charlist)] @comment))
+  (#match? @_attribute "^(moduledoc|typedoc|doc)$"))
+
+(escape_sequence) @string.escape
+[
+  (string)
+  (charlist)
+  (sigil)
+] @string
+
+[
+  (atom)
+  (quoted_atom)
+  (keyword)
+  (quoted_keyword)
+] @string.special
+
+[
+  (integer)
+  (float)
+  (char)
+] @number
+
+(boolean) @boolean
+(nil) @constant.null
+
+(alias) @identifier.module
+
+(unary_operator
+  operator: "@" @identifier.attribute
+  operand: [
+    (identifier) @identifier.attribute
+    (call target: (identifier) @identifier.attribute)
+  ])
+
+;; def, defmodule, if, case, ... are calls too
+((call target: (identifier) @keyword)
+ (#match? @keyword "^(def|defp|defdelegate|defexception|defguard|defguardp|defimpl|defmacro|defmacrop|defmodule|defoverridable|defprotocol|defstruct|alias|case|cond|for|if|import|quote|raise|receive|require|reraise|super|throw|try|unless|unquote|unquote_splicing|use|with)$"))
+
+(call target: (identifier) @identifier.function)
+(call target: (dot right: (identifier) @identifier.function))
+(binary_operator operator: "|>" right: (identifier) @identifier.function)
+
+[
+  "when"
+  "and"
+  "or"
+  "not"
+  "in"
+  "fn"
+  "do"
+  "end"
+  "catch"
+  "rescue"
+  "after"
+  "else"
+] @keyword
+
+(operator_identifier) @operator
+(unary_operator operator: _ @operator)
+(binary_operator operator: _ @operator)
+(stab_clause operator: _ @operator)
+
+[
+  ","
+  ";"
+] @punctuation.delimiter
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+  "<<"
+  ">>"
+] @punctuation.bracket
+
+((identifier) @variable.builtin
+ (#match? @variable.builtin "^__(MODULE|DIR|ENV|CALLER|STACKTRACE)__$"))
+(identifier) @variable
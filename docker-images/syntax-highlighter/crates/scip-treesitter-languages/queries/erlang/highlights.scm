@@ -0,0 +1,71 @@
+(comment) @comment
+
+[
+  (string)
+  (char)
+] @string
+
+[
+  (integer)
+  (float)
+] @number
+
+(module_attribute name: (atom) @identifier.module)
+(remote_module module: (atom) @identifier.module)
+
+(function_clause name: (atom) @identifier.function)
+(call expr: (atom) @identifier.function)
+(remote fun: (atom) @identifier.function)
+
+(record_decl name: (atom) @type)
+(record_expr name: (record_name name: (atom) @type))
+(type_name name: (atom) @type)
+(record_field name: (atom) @property)
+
+[
+  "after"
+  "begin"
+  "case"
+  "catch"
+  "end"
+  "fun"
+  "if"
+  "of"
+  "receive"
+  "try"
+  "when"
+] @keyword
+
+[
+  "andalso"
+  "orelse"
+  "->"
+  "="
+  "!"
+  "|"
+  "||"
+  "<-"
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+  "<<"
+  ">>"
+] @punctuation.bracket
+
+[
+  ","
+  ";"
+  "."
+  ":"
+] @punctuation.delimiter
+
+;; Atoms are symbols, unless they are the name of something above
+(atom) @string.special
+
+(var) @variable
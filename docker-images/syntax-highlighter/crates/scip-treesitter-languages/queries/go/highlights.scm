@@ -16,7 +16,7 @@
 (variadic_parameter_declaration (identifier) @variable.parameter)
 
 (call_expression
-  function: (identifier) @identifer.function)
+  function: (identifier) @identifier.function)
 
 (call_expression
   function: (selector_expression
@@ -25,12 +25,12 @@
 ; Function definitions
 
 (method_spec
- name: (field_identifier) @identifier.function)
+ name: (field_identifier) @identifier.function.definition)
 (function_declaration
- name: (identifier) @identifier.function)
+ name: (identifier) @identifier.function.definition)
 
 (method_declaration
- name: (field_identifier) @identifier.function)
+ name: (field_identifier) @identifier.function.definition)
 
 ; Constants
 
@@ -0,0 +1,75 @@
+(comment) @comment
+(description) @comment
+
+(string_value) @string
+
+[
+  (int_value)
+  (float_value)
+] @number
+
+(boolean_value) @boolean
+(null_value) @constant.null
+(enum_value) @constant
+
+(variable) @variable.parameter
+
+[
+  (object_type_definition (name))
+  (interface_type_definition (name))
+  (enum_type_definition (name))
+  (input_object_type_definition (name))
+  (union_type_definition (name))
+  (scalar_type_definition (name))
+  (named_type (name))
+] @type
+
+(operation_definition (name) @identifier.function)
+(fragment_name (name) @identifier.function)
+(field_definition (name) @property)
+(input_value_definition (name) @variable.parameter)
+(field (name) @property)
+(argument (name) @variable.parameter)
+(directive (name) @identifier.attribute)
+
+[
+  "query"
+  "mutation"
+  "subscription"
+  "fragment"
+  "on"
+  "type"
+  "interface"
+  "enum"
+  "input"
+  "union"
+  "scalar"
+  "schema"
+  "extend"
+  "implements"
+  "directive"
+] @keyword
+
+[
+  "="
+  "|"
+  "&"
+  "!"
+  "..."
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ":"
+  ","
+] @punctuation.delimiter
+
+"@" @identifier.attribute
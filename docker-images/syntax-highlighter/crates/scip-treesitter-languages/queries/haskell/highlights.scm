@@ -0,0 +1,68 @@
+(comment) @comment
+
+[
+  (string)
+  (char)
+] @string
+
+[
+  (integer)
+  (float)
+] @number
+
+(module) @identifier.module
+
+(signature name: (variable) @identifier.function)
+(function name: (variable) @identifier.function)
+
+[
+  (type)
+  (constructor)
+] @type
+
+(type_variable) @variable.parameter
+
+(operator) @operator
+
+[
+  "::"
+  "->"
+  "=>"
+  "<-"
+  "="
+  "|"
+] @operator
+
+[
+  (where)
+  "module"
+  "import"
+  "qualified"
+  "as"
+  "hiding"
+  "data"
+  "newtype"
+  "type"
+  "class"
+  "instance"
+  "deriving"
+  "let"
+  "in"
+  "case"
+  "of"
+  "if"
+  "then"
+  "else"
+  "do"
+] @keyword
+
+[
+  "("
+  ")"
+  "["
+  "]"
+] @punctuation.bracket
+
+(comma) @punctuation.delimiter
+
+(variable) @variable
@@ -0,0 +1,75 @@
+; Blocks and attributes
+
+(config_file (body (block (identifier) @keyword)))
+(block (identifier) @type)
+(attribute (identifier) @property)
+
+; Function calls
+
+(function_call (identifier) @identifier.function)
+
+; Builtin variables
+
+((identifier) @variable.builtin
+  (#match? @variable.builtin "^(count|data|each|local|module|path|self|terraform|var)$"))
+
+(identifier) @variable
+
+; Literals
+
+(comment) @comment
+(numeric_lit) @number
+(bool_lit) @boolean
+(null_lit) @constant.null
+
+[
+  (quoted_template_start)
+  (quoted_template_end)
+  (template_literal)]
+@string
+
+[
+  (heredoc_identifier)
+  (heredoc_start)]
+@punctuation.delimiter
+
+[
+  (template_interpolation_start)
+  (template_interpolation_end)]
+@punctuation.delimiter
+
+; Punctuation
+
+[
+  (block_start)
+  (block_end)
+  (object_start)
+  (object_end)
+  (tuple_start)
+  (tuple_end)
+  "("
+  ")"
+  "["
+  "]"]
+@punctuation.bracket
+
+[ "." "," ":" ] @punctuation.delimiter
+
+[
+  "="
+  "=="
+  "!="
+  "<"
+  "<="
+  ">"
+  ">="
+  "&&"
+  "||"
+  "!"
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "?"]
+@operator
@@ -0,0 +1,20 @@
+(tag_name) @tag
+(erroneous_end_tag_name) @tag
+(attribute_name) @tag.attribute
+[
+  (attribute_value)
+  (quoted_attribute_value)]
+@string
+
+(doctype) @keyword
+(comment) @comment
+
+[
+  "<"
+  ">"
+  "</"
+  "/>"
+  "<!"]
+@tag.delimiter
+
+"=" @operator
@@ -0,0 +1,17 @@
+(pair key: (string) @property)
+
+(string) @string
+(escape_sequence) @string.escape
+
+(number) @number
+[ (true) (false) ] @boolean
+(null) @constant.null
+
+[ "," ":" ] @punctuation.delimiter
+
+[
+  "["
+  "]"
+  "{"
+  "}"]
+@punctuation.bracket
@@ -0,0 +1,106 @@
+(comment) @comment
+
+(string) @string
+(escape_sequence) @string.escape
+
+(number) @number
+[
+  (true)
+  (false)
+] @boolean
+(nil) @constant.null
+(vararg_expression) @constant.builtin
+
+(function_declaration
+  name: [
+    (identifier) @identifier.function
+    (dot_index_expression field: (identifier) @identifier.function)
+    (method_index_expression method: (identifier) @identifier.function)
+  ])
+
+((function_call
+   name: (identifier) @function.builtin)
+ (#match? @function.builtin "^(assert|error|ipairs|next|pairs|pcall|print|rawget|rawset|require|select|setmetatable|getmetatable|tonumber|tostring|type|xpcall)$"))
+
+(function_call
+  name: [
+    (identifier) @identifier.function
+    (dot_index_expression field: (identifier) @identifier.function)
+    (method_index_expression method: (identifier) @identifier.function)
+  ])
+
+(parameters (identifier) @variable.parameter)
+
+(field name: (identifier) @property)
+(dot_index_expression field: (identifier) @property)
+
+(attribute (identifier) @identifier.attribute)
+
+[
+  "and"
+  "do"
+  "else"
+  "elseif"
+  "end"
+  "for"
+  "function"
+  "goto"
+  "if"
+  "in"
+  "local"
+  "not"
+  "or"
+  "repeat"
+  "return"
+  "then"
+  "until"
+  "while"
+] @keyword
+(break_statement) @keyword
+
+[
+  "+"
+  "-"
+  "*"
+  "/"
+  "//"
+  "%"
+  "^"
+  "#"
+  "&"
+  "~"
+  "|"
+  "<<"
+  ">>"
+  ".."
+  "=="
+  "~="
+  "<"
+  "<="
+  ">"
+  ">="
+  "="
+] @operator
+
+[
+  ";"
+  ":"
+  "::"
+  ","
+  "."
+] @punctuation.delimiter
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+((identifier) @variable.builtin
+ (#eq? @variable.builtin "self"))
+((identifier) @constant
+ (#match? @constant "^[A-Z][A-Z_0-9]*$"))
+(identifier) @variable
@@ -0,0 +1,52 @@
+(comment) @comment
+
+[
+  "ifeq"
+  "ifneq"
+  "ifdef"
+  "ifndef"
+  "else"
+  "endif"
+  "define"
+  "endef"
+  "include"
+  "-include"
+  "sinclude"
+  "override"
+  "export"
+  "unexport"
+  "vpath"
+] @keyword
+
+(rule (targets (word) @identifier.function))
+(prerequisites (word) @identifier)
+
+(variable_assignment name: (word) @variable)
+(define_directive name: (word) @variable)
+(variable_reference (word) @variable)
+(automatic_variable) @variable.builtin
+
+[
+  "="
+  ":="
+  "::="
+  "?="
+  "+="
+  "!="
+] @operator
+
+[
+  ":"
+  "::"
+  "|"
+  ";"
+] @punctuation.delimiter
+
+[
+  "$"
+  "$$"
+  "("
+  ")"
+  "{"
+  "}"
+] @punctuation.bracket
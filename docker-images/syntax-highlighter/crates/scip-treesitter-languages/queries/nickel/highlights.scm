@@ -1,5 +1,4 @@
-(comment) @comment @spell
-(annot_atom doc: (static_string) @spell)
+(comment) @comment
 
 [
   "forall"
@@ -59,13 +58,8 @@
 (interpolation_start) @punctuation.bracket
 (interpolation_end) @punctuation.bracket
 
-(record_field) @field
-
 (builtin) @function.builtin
 
-(fun_expr pats:
-  (pattern id:
-    (ident) @parameter))
 
 
 
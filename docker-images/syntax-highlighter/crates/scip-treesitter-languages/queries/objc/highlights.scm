@@ -0,0 +1,21 @@
+;; The c patterns are included at the end, so that ours take precedence
+[
+  "@interface"
+  "@implementation"
+  "@protocol"
+  "@end"
+] @keyword
+
+(class_interface "@interface" . (identifier) @type)
+(class_interface category: (identifier) @type)
+(class_implementation "@implementation" . (identifier) @type)
+(class_implementation category: (identifier) @type)
+(protocol_declaration "@protocol" . (identifier) @type)
+
+(method_declaration (identifier) @identifier.function)
+(method_definition (identifier) @identifier.function)
+
+((identifier) @variable.builtin
+ (#match? @variable.builtin "^(self|super)$"))
+
+; inherits: c
@@ -0,0 +1,92 @@
+(comment) @comment
+
+[
+  (string)
+  (character)
+] @string
+(escape_sequence) @string.escape
+
+(number) @number
+(boolean) @boolean
+(unit) @constant.builtin
+
+[
+  (module_name)
+  (module_type_name)
+] @identifier.module
+
+[
+  (type_constructor)
+  (constructor_name)
+] @type
+
+(field_name) @property
+
+(let_binding pattern: (value_name) @identifier.function (parameter))
+(value_specification (value_name) @identifier.function)
+(external (value_name) @identifier.function)
+(application_expression function: (value_path (value_name) @identifier.function))
+
+(parameter (value_pattern) @variable.parameter)
+
+[
+  "and"
+  "begin"
+  "else"
+  "end"
+  "external"
+  "fun"
+  "function"
+  "if"
+  "in"
+  "include"
+  "let"
+  "match"
+  "module"
+  "mutable"
+  "of"
+  "open"
+  "rec"
+  "sig"
+  "struct"
+  "then"
+  "try"
+  "type"
+  "val"
+  "when"
+  "with"
+] @keyword
+
+[
+  (prefix_operator)
+  (sign_operator)
+  (pow_operator)
+  (mult_operator)
+  (add_operator)
+  (concat_operator)
+  (rel_operator)
+  (and_operator)
+  (or_operator)
+  (assign_operator)
+  "->"
+  "="
+  "|"
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ","
+  ";"
+  ":"
+  "."
+] @punctuation.delimiter
+
+(value_name) @variable
@@ -0,0 +1,79 @@
+(comment) @comment
+(string) @string
+
+[
+  (int_lit)
+  (float_lit)
+] @number
+
+(package (full_ident) @identifier.module)
+
+[
+  (message_name)
+  (enum_name)
+  (service_name)
+  (message_or_enum_type)
+] @type
+
+(rpc_name) @identifier.function
+
+(field (identifier) @property)
+(map_field (identifier) @property)
+(oneof_field (identifier) @property)
+(enum_field (identifier) @constant)
+
+[
+  "bool"
+  "bytes"
+  "double"
+  "fixed32"
+  "fixed64"
+  "float"
+  "int32"
+  "int64"
+  "sfixed32"
+  "sfixed64"
+  "sint32"
+  "sint64"
+  "string"
+  "uint32"
+  "uint64"
+] @type.builtin
+
+[
+  "enum"
+  "import"
+  "map"
+  "message"
+  "oneof"
+  "option"
+  "package"
+  "repeated"
+  "reserved"
+  "returns"
+  "rpc"
+  "service"
+  "stream"
+  "syntax"
+] @keyword
+
+"=" @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+  "<"
+  ">"
+] @punctuation.bracket
+
+[
+  ";"
+  ","
+  "."
+] @punctuation.delimiter
+
+(identifier) @variable
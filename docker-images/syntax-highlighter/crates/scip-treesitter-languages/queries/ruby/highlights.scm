@@ -82,8 +82,6 @@
 ((constant) @constant
  (#match? @constant "^[A-Z\\d_]+$"))
 
-(constant) @constructor
-
 (self) @identifier.builtin
 (super) @identifier.builtin
 
@@ -56,7 +56,7 @@
 (fragment_specifier) @type
 
 (macro_invocation
-  macro: (identifier) @identifier.function
+  macro: (identifier) @function.macro
   "!" @identifier.builtin)
 
 ; Function definitions
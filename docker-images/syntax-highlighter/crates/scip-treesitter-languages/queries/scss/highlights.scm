@@ -0,0 +1,18 @@
+;; The css patterns are included at the end, so that ours take precedence
+(single_line_comment) @comment
+
+(mixin_statement (name) @identifier.function)
+(function_statement (name) @identifier.function)
+(include_statement (identifier) @identifier.function)
+
+[
+  "@use"
+  "@forward"
+  "@mixin"
+  "@include"
+  "@function"
+  "@return"
+  "@extend"]
+@keyword
+
+; inherits: css
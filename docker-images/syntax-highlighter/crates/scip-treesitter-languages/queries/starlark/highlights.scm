@@ -0,0 +1,87 @@
+(comment) @comment
+
+[
+  (string)
+  (concatenated_string)
+] @string
+
+(escape_sequence) @string.escape
+
+[
+  (integer)
+  (float)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+(none) @constant.null
+
+(function_definition name: (identifier) @identifier.function)
+
+((call function: (identifier) @function.builtin)
+ (#match? @function.builtin "^(load|rule|repository_rule|module_extension|provider|aspect|select|glob|attr|struct|depset|fail|print|len|str|int|list|dict|enumerate|range|sorted|getattr|hasattr|package)$"))
+(call function: (identifier) @identifier.function)
+(call function: (attribute attribute: (identifier) @identifier.function))
+
+(keyword_argument name: (identifier) @variable.parameter)
+(parameters (identifier) @variable.parameter)
+(default_parameter name: (identifier) @variable.parameter)
+
+(attribute attribute: (identifier) @identifier.attribute)
+
+((identifier) @constant
+ (#match? @constant "^[A-Z][A-Z_0-9]*$"))
+
+(identifier) @variable
+
+[
+  "and"
+  "break"
+  "continue"
+  "def"
+  "elif"
+  "else"
+  "for"
+  "if"
+  "in"
+  "lambda"
+  "not"
+  "or"
+  "pass"
+  "return"
+] @keyword
+
+[
+  "-"
+  "+"
+  "*"
+  "/"
+  "//"
+  "%"
+  "="
+  "+="
+  "=="
+  "!="
+  "<"
+  "<="
+  ">"
+  ">="
+] @operator
+
+[
+  ","
+  "."
+  ":"
+] @punctuation.delimiter
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
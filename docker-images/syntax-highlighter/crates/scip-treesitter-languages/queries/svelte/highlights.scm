@@ -0,0 +1,17 @@
+(tag_name) @tag
+(attribute_name) @tag.attribute
+[
+  (attribute_value)
+  (quoted_attribute_value)]
+@string
+
+(comment) @comment
+
+[
+  "<"
+  ">"
+  "</"
+  "/>"]
+@tag.delimiter
+
+"=" @operator
@@ -0,0 +1,28 @@
+;; <script lang="ts"> and <style lang="scss">, javascript and css otherwise
+((script_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#match? @_tag "lang=[\"']?(ts|typescript)[\"' >]")
+ (#set! injection.language "typescript"))
+
+((script_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#not-match? @_tag "lang=[\"']?(ts|typescript)[\"' >]")
+ (#set! injection.language "javascript"))
+
+((style_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#match? @_tag "lang=[\"']?scss[\"' >]")
+ (#set! injection.language "scss"))
+
+((style_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#not-match? @_tag "lang=")
+ (#set! injection.language "css"))
+
+;; {count + 1} and on:click={handler}
+((raw_text_expr) @injection.content
+ (#set! injection.language "javascript"))
@@ -0,0 +1,168 @@
+[
+  (comment)
+  (multiline_comment)
+] @comment
+
+; Strings
+
+[
+  (line_str_text)
+  (multi_line_str_text)
+  "\""
+  "\"\"\""
+] @string
+
+(str_escaped_char) @string.escape
+
+(line_string_literal
+  [
+    "\\("
+    ")"
+  ] @string.escape)
+
+; Literals
+
+[
+  (integer_literal)
+  (hex_literal)
+  (oct_literal)
+  (bin_literal)
+  (real_literal)
+] @number
+
+(boolean_literal) @boolean
+"nil" @constant.null
+
+; Declarations
+
+(function_declaration name: (simple_identifier) @identifier.function)
+(protocol_function_declaration name: (simple_identifier) @identifier.function)
+(init_declaration "init" @identifier.function)
+
+(parameter external_name: (simple_identifier) @variable.parameter)
+(parameter name: (simple_identifier) @variable.parameter)
+
+(type_identifier) @type
+
+[
+  (self_expression)
+  (super_expression)
+] @variable.builtin
+
+; Calls
+
+(call_expression (simple_identifier) @identifier.function)
+(call_expression
+  (navigation_expression
+    (navigation_suffix (simple_identifier) @identifier.function)))
+
+(navigation_suffix (simple_identifier) @property)
+
+; Keywords
+
+[
+  (visibility_modifier)
+  (member_modifier)
+  (function_modifier)
+  (property_modifier)
+  (parameter_modifier)
+  (inheritance_modifier)
+  (mutation_modifier)
+  (throws)
+  (where_keyword)
+  (getter_specifier)
+  (setter_specifier)
+  (else)
+  (as_operator)
+  (try_operator)
+  (throw_keyword)
+  (catch_keyword)
+  (default_keyword)
+] @keyword
+
+[
+  "func"
+  "init"
+  "deinit"
+  "import"
+  "protocol"
+  "extension"
+  "enum"
+  "struct"
+  "class"
+  "typealias"
+  "let"
+  "var"
+  "case"
+  "return"
+  "if"
+  "guard"
+  "switch"
+  "for"
+  "in"
+  "while"
+  "repeat"
+  "break"
+  "continue"
+  "do"
+  "async"
+  "await"
+  "some"
+  "override"
+  "convenience"
+  "required"
+  "weak"
+  "unowned"
+  "didSet"
+  "willSet"
+  "subscript"
+] @keyword
+
+; Operators and punctuation
+
+[
+  "+"
+  "-"
+  "*"
+  "/"
+  "%"
+  "="
+  "+="
+  "-="
+  "*="
+  "/="
+  "<"
+  ">"
+  "<="
+  ">="
+  "&&"
+  "||"
+  "!="
+  "=="
+  "==="
+  "?"
+  "??"
+  "->"
+  "..<"
+  "..."
+  (bang)
+  (custom_operator)
+] @operator
+
+[
+  "."
+  ";"
+  ":"
+  ","
+] @punctuation.delimiter
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+
+(simple_identifier) @variable
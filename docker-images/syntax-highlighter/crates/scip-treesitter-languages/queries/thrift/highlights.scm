@@ -0,0 +1,55 @@
+(comment) @comment
+(string) @string
+(number) @number
+(boolean) @boolean
+
+(namespace_declaration (identifier) @identifier.module)
+
+(struct_definition . (identifier) @type)
+(union_definition . (identifier) @type)
+(exception_definition . (identifier) @type)
+(enum_definition . (identifier) @type)
+(service_definition (identifier) @type)
+(typedef_definition (identifier) @type)
+
+(function_definition (identifier) @identifier.function)
+(field (identifier) @property)
+
+(primitive) @type.builtin
+
+[
+  "const"
+  "enum"
+  "exception"
+  "extends"
+  "include"
+  "namespace"
+  "oneway"
+  "optional"
+  "required"
+  "service"
+  "struct"
+  "throws"
+  "typedef"
+  "union"
+  "void"
+] @keyword
+
+"=" @operator
+
+[
+  "("
+  ")"
+  "{"
+  "}"
+  "<"
+  ">"
+] @punctuation.bracket
+
+[
+  ","
+  ";"
+  ":"
+] @punctuation.delimiter
+
+(identifier) @variable
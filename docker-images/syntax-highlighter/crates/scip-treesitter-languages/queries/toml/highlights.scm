@@ -0,0 +1,37 @@
+; Keys
+
+(bare_key) @property
+(quoted_key) @property
+
+; Literals
+
+(boolean) @boolean
+(comment) @comment
+(string) @string
+(escape_sequence) @string.escape
+[
+  (integer)
+  (float)]
+@number
+
+[
+  (offset_date_time)
+  (local_date_time)
+  (local_date)
+  (local_time)]
+@string.special
+
+; Punctuation
+
+[ "." "," ] @punctuation.delimiter
+
+"=" @operator
+
+[
+  "["
+  "]"
+  "[["
+  "]]"
+  "{"
+  "}"]
+@punctuation.bracket
@@ -1,2 +1,7 @@
-;; This file inherits from typescript/highlights.scm
+;; The typescript patterns are included at the end, so that ours take precedence
+(jsx_opening_element name: (identifier) @tag)
+(jsx_closing_element name: (identifier) @tag)
+(jsx_self_closing_element name: (identifier) @tag)
 (jsx_attribute (property_identifier) @identifier.attribute)
+
+; inherits: typescript
@@ -1,4 +1,4 @@
-;; This file inherits from javascript/highlights.scm
+;; The javascript patterns are included at the end, so that ours take precedence
 (type_identifier) @type
 (predefined_type) @type.builtin
 [
@@ -19,3 +19,5 @@
   "satisfies"
   "type"
   "typeof"] @keyword
+
+; inherits: javascript
@@ -0,0 +1,25 @@
+(tag_name) @tag
+(attribute_name) @tag.attribute
+[
+  (attribute_value)
+  (quoted_attribute_value)]
+@string
+
+(directive_name) @keyword
+(directive_argument) @tag.attribute
+
+(comment) @comment
+
+[
+  "<"
+  ">"
+  "</"
+  "/>"]
+@tag.delimiter
+
+"=" @operator
+
+[
+  "{{"
+  "}}"]
+@punctuation.bracket
@@ -0,0 +1,40 @@
+;; <script lang="ts"> and <style lang="scss">, javascript and css otherwise
+((script_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#match? @_tag "lang=[\"']?(ts|typescript)[\"' >]")
+ (#set! injection.language "typescript"))
+
+((script_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#match? @_tag "lang=[\"']?tsx[\"' >]")
+ (#set! injection.language "tsx"))
+
+((script_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#not-match? @_tag "lang=[\"']?(ts|typescript|tsx)[\"' >]")
+ (#set! injection.language "javascript"))
+
+((style_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#match? @_tag "lang=[\"']?scss[\"' >]")
+ (#set! injection.language "scss"))
+
+((style_element
+  (start_tag) @_tag
+  (raw_text) @injection.content)
+ (#not-match? @_tag "lang=")
+ (#set! injection.language "css"))
+
+;; {{ count + 1 }} and v-if="visible"
+((interpolation
+  (raw_text) @injection.content)
+ (#set! injection.language "javascript"))
+
+((directive_attribute
+  (quoted_attribute_value
+    (attribute_value) @injection.content))
+ (#set! injection.language "javascript"))
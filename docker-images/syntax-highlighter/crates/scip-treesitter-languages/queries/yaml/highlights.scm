@@ -0,0 +1,74 @@
+; Keys, before the scalars so that they win
+
+(_
+  key: (flow_node
+         [
+           (double_quote_scalar)
+           (single_quote_scalar)]
+         @property))
+
+(_
+  key: (flow_node
+         (plain_scalar
+           (string_scalar) @property)))
+
+; Scalars
+
+(boolean_scalar) @boolean
+(null_scalar) @constant.null
+
+[
+  (double_quote_scalar)
+  (single_quote_scalar)
+  (block_scalar)
+  (string_scalar)]
+@string
+
+(escape_sequence) @string.escape
+
+[
+  (integer_scalar)
+  (float_scalar)]
+@number
+
+(comment) @comment
+
+; Anchors, aliases and tags
+
+[
+  (anchor_name)
+  (alias_name)]
+@identifier.constant
+
+(tag) @type
+
+[
+  (yaml_directive)
+  (tag_directive)
+  (reserved_directive)]
+@keyword
+
+; Punctuation
+
+[
+  ","
+  "-"
+  ":"
+  ">"
+  "?"
+  "|"]
+@punctuation.delimiter
+
+[
+  "["
+  "]"
+  "{"
+  "}"]
+@punctuation.bracket
+
+[
+  "*"
+  "&"
+  "---"
+  "..."]
+@punctuation
@@ -118,7 +118,7 @@ field_constant: (IDENTIFIER) @constant
 (BUILTINIDENTIFIER) @function.builtin
 
 ((BUILTINIDENTIFIER) @include
-  (#any-of? @include "@import" "@cImport"))
+  (#match? @include "^@(import|cImport)$"))
 
 parameter: (IDENTIFIER) @variable.parameter
 
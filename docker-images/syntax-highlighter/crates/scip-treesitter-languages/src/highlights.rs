@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 
-use paste::paste;
+use anyhow::{anyhow, bail, Result};
+use protobuf::Enum;
 use scip::types::SyntaxKind;
-use scip_macros::include_scip_query;
 use tree_sitter_highlight::{Highlight, HighlightConfiguration};
 
-use crate::parsers::BundledParser;
+use crate::{
+    parsers::{BundledParser, ALL_BUNDLED, SYNTAX_KIND_OVERLAYS},
+    plugins::get_plugin,
+};
 
 #[rustfmt::skip]
 // Table of (@CaptureGroup, SyntaxKind) mapping.
@@ -18,9 +21,11 @@ use crate::parsers::BundledParser;
 // We can also define our own new capture types that we want to use and add to queries to provide
 // particular highlights if necessary.
 //
-// (I can also add per-language mappings for these if we want, but you could also just do that with
-//  unique match groups. For example `@rust-bracket`, or similar. That doesn't need any
-//  particularly new rust code to be written. You can just modify queries for that)
+// Languages can refine this table with a `syntax-kinds.txt` next to their queries, for
+// captures that only have a more specific SyntaxKind in some languages (e.g. `@tag` is
+// only a Tag in markup and JSX). An entry for a capture that is in the shared table
+// replaces it for that language. The files are collected in SYNTAX_KIND_OVERLAYS by
+// the bundled_languages! table in crate::parsers.
 const MATCHES_TO_SYNTAX_KINDS: &[(&str, SyntaxKind)] = &[
     ("boolean",                 SyntaxKind::BooleanLiteral),
     ("character",               SyntaxKind::CharacterLiteral),
@@ -72,104 +77,173 @@ const MATCHES_TO_SYNTAX_KINDS: &[(&str, SyntaxKind)] = &[
     ("variable.module",         SyntaxKind::IdentifierModule),
 ];
 
-/// Add a language highlight configuration to the CONFIGURATIONS global.
-///
-/// This makes it so you don't have to understand how configurations are added,
-/// just add the name of filetype that you want.
-macro_rules! create_configurations {
-    ( $($name: tt),* ) => {{
-        use crate::parsers::BundledParser;
+// Can't be part of a capture name, so highlight names replaced by this never match
+const UNUSED_HIGHLIGHT_NAME: &str = " ";
 
-        let mut m = HashMap::new();
-        let highlight_names = MATCHES_TO_SYNTAX_KINDS.iter().map(|hl| hl.0).collect::<Vec<&str>>();
-
-        $(
-            {
-                // Create HighlightConfiguration language
-                let mut lang = HighlightConfiguration::new(
-                    paste! { BundledParser::$name.get_language() },
-                    include_scip_query!($name, "highlights"),
-                    include_scip_query!($name, "injections"),
-                    include_scip_query!($name, "locals"),
-                ).expect(stringify!("parser for '{}' must be compiled", $name));
-
-                // Associate highlights with configuration
-                lang.configure(&highlight_names);
-
-                // Insert into configurations, so we only create once at startup.
-                m.insert(BundledParser::$name, lang);
-            }
-        )*
-
-        // Manually insert the typescript and tsx languages because the
-        // tree-sitter-typescript crate doesn't have a language() function.
-        {
-            let highlights = vec![
-                include_scip_query!("typescript", "highlights"),
-                include_scip_query!("javascript", "highlights"),
-            ];
-            let mut lang = HighlightConfiguration::new(
-                BundledParser::Typescript.get_language(),
-                &highlights.join("\n"),
-                include_scip_query!("typescript", "injections"),
-                include_scip_query!("typescript", "locals"),
-            ).expect("parser for 'typescript' must be compiled");
-            lang.configure(&highlight_names);
-            m.insert(BundledParser::Typescript, lang);
+struct HighlightName {
+    // None for the shared table
+    parser: Option<BundledParser>,
+    capture: &'static str,
+    kind: SyntaxKind,
+}
+
+lazy_static::lazy_static! {
+    // Every language is configured with all of these names, so that a Highlight means
+    // the same thing in every language, including injected ones. Names of overlays of
+    // other languages are replaced by UNUSED_HIGHLIGHT_NAME.
+    //
+    // Overlays come first: HighlightConfiguration::configure picks the first of the
+    // longest matching names, so an overlay wins over the same name in the shared table.
+    static ref HIGHLIGHT_NAMES: Vec<HighlightName> = {
+        let mut names = vec![];
+        for &(parser, overlay) in SYNTAX_KIND_OVERLAYS {
+            let overlay = parse_syntax_kinds(overlay).unwrap_or_else(|err| {
+                panic!("invalid syntax-kinds.txt for {}: {err:#}", parser.get_language_name())
+            });
+            names.extend(overlay.into_iter().map(|(capture, kind)| HighlightName {
+                parser: Some(parser),
+                capture,
+                kind,
+            }));
         }
-        {
-            let highlights = vec![
-                include_scip_query!("tsx", "highlights"),
-                include_scip_query!("typescript", "highlights"),
-                include_scip_query!("javascript", "highlights"),
-            ];
-            let mut lang = HighlightConfiguration::new(
-                BundledParser::Tsx.get_language(),
-                &highlights.join("\n"),
-                include_scip_query!("tsx", "injections"),
-                include_scip_query!("tsx", "locals"),
-            ).expect("parser for 'tsx' must be compiled");
-            lang.configure(&highlight_names);
-            m.insert(BundledParser::Tsx, lang);
+
+        names.extend(MATCHES_TO_SYNTAX_KINDS.iter().map(|&(capture, kind)| HighlightName {
+            parser: None,
+            capture,
+            kind,
+        }));
+        names
+    };
+}
+
+/// Parses a `syntax-kinds.txt` file. Every line has a capture name and the name of a
+/// SyntaxKind separated by whitespace, e.g. `function.macro IdentifierMacro`. Comments
+/// start with `;`, like in queries.
+pub fn parse_syntax_kinds(text: &str) -> Result<Vec<(&str, SyntaxKind)>> {
+    let mut kinds = vec![];
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
         }
 
-        m
-    }}
+        let (capture, kind) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [capture, kind] => (capture.trim_start_matches('@'), kind),
+            _ => bail!("line {}: expected a capture and a SyntaxKind", idx + 1),
+        };
+
+        // Only canonical names, like in the snapshots. Deprecated aliases are not in VALUES.
+        let kind = SyntaxKind::VALUES
+            .iter()
+            .find(|k| format!("{k:?}") == kind)
+            .copied()
+            .ok_or_else(|| anyhow!("line {}: unknown SyntaxKind {kind:?}", idx + 1))?;
+
+        kinds.push((capture, kind));
+    }
+
+    Ok(kinds)
 }
 
 lazy_static::lazy_static! {
+    // Created once at startup. Languages that weren't compiled in are left out, so
+    // lookups return None. New languages are added to the table in crate::parsers.
     pub static ref CONFIGURATIONS: HashMap<BundledParser, HighlightConfiguration> = {
-        // NOTE: typescript/tsx crates are included, even though not listed below.
-
-        // You can add any new crate::parsers::Parser variants here.
-        create_configurations!(
-            C,
-            Cpp,
-            C_Sharp,
-            Go,
-            Java,
-            Javascript,
-            Jsonnet,
-            Kotlin,
-            Matlab,
-            Nickel,
-            Perl,
-            Pod,
-            Python,
-            Ruby,
-            Rust,
-            Scala,
-            Sql,
-            Xlsg,
-            Zig
-        )
+        let mut m = HashMap::new();
+
+        for &parser in ALL_BUNDLED {
+            let Some((highlights, injections, locals)) = parser.highlight_queries() else {
+                continue;
+            };
+
+            let mut lang =
+                HighlightConfiguration::new(parser.get_language(), highlights, injections, locals)
+                    .unwrap_or_else(|err| {
+                        panic!("queries for '{}' must compile: {err}", parser.get_language_name())
+                    });
+
+            // Associate highlights with configuration
+            lang.configure(&highlight_names(Some(parser)));
+
+            m.insert(parser, lang);
+        }
+
+        m
     };
 }
 
+/// The capture names that highlights are reported for in a language, in the order of
+/// [`Highlight`] ids. Plugins (None) only use the shared table.
+pub(crate) fn highlight_names(parser: Option<BundledParser>) -> Vec<&'static str> {
+    HIGHLIGHT_NAMES
+        .iter()
+        .map(|name| match name.parser {
+            Some(owner) if Some(owner) != parser => UNUSED_HIGHLIGHT_NAME,
+            _ => name.capture,
+        })
+        .collect()
+}
+
 pub fn get_highlighting_configuration(filetype: &str) -> Option<&'static HighlightConfiguration> {
-    BundledParser::get_parser(filetype).and_then(|parser| CONFIGURATIONS.get(&parser))
+    match BundledParser::get_parser(filetype)? {
+        BundledParser::Plugin(id) => get_plugin(id)?.highlight_configuration.as_ref(),
+        parser => CONFIGURATIONS.get(&parser),
+    }
 }
 
 pub fn get_syntax_kind_for_hl(hl: Highlight) -> SyntaxKind {
-    MATCHES_TO_SYNTAX_KINDS[hl.0].1
+    HIGHLIGHT_NAMES[hl.0].kind
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_syntax_kinds() {
+        let kinds = parse_syntax_kinds(
+            "; comment\n@function.macro   IdentifierMacro\n\ntag Tag ; trailing comment\n",
+        )
+        .unwrap();
+        assert_eq!(
+            kinds,
+            vec![
+                ("function.macro", SyntaxKind::IdentifierMacro),
+                ("tag", SyntaxKind::Tag)
+            ]
+        );
+
+        assert!(parse_syntax_kinds("tag NoSuchKind").is_err());
+        assert!(parse_syntax_kinds("tag").is_err());
+    }
+
+    #[test]
+    fn test_overlays_only_apply_to_their_language() {
+        let rust = highlight_names(Some(BundledParser::Rust));
+        let go = highlight_names(Some(BundledParser::Go));
+        assert_eq!(rust.len(), go.len());
+
+        let overlay = rust
+            .iter()
+            .position(|name| *name == "function.macro")
+            .unwrap();
+        assert_eq!(go[overlay], UNUSED_HIGHLIGHT_NAME);
+        assert_eq!(
+            get_syntax_kind_for_hl(Highlight(overlay)),
+            SyntaxKind::IdentifierMacro
+        );
+    }
+
+    #[test]
+    fn test_tags_are_only_tags_in_markup() {
+        let kind = |parser| {
+            let names = highlight_names(Some(parser));
+            let idx = names.iter().position(|name| *name == "tag").unwrap();
+            get_syntax_kind_for_hl(Highlight(idx))
+        };
+
+        assert_eq!(kind(BundledParser::Html), SyntaxKind::Tag);
+        assert_eq!(kind(BundledParser::Tsx), SyntaxKind::Tag);
+        assert_eq!(kind(BundledParser::Go), SyntaxKind::UnspecifiedSyntaxKind);
+    }
 }
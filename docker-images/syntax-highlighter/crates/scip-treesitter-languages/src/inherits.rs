@@ -0,0 +1,153 @@
+//! Sharing of queries between languages whose grammars build on each other.
+//!
+//! A line `; inherits: javascript` (or `; inherits: c,cpp` for several languages) in a
+//! query file is replaced with the same query file of those languages, which can
+//! inherit from others in turn. Every file is included at most once, and cycles are
+//! an error.
+//!
+//! Where the line goes matters: tree-sitter-highlight uses the first pattern that
+//! matches a node, so a highlights query that wants to override its parent puts the
+//! line at the end. For other queries it usually goes at the top.
+//!
+//! Bundled queries are resolved at compile time by `include_scip_query!`, which
+//! follows the same rules. This module resolves queries read at runtime (plugins and
+//! tests).
+
+use anyhow::{bail, Result};
+
+/// The languages of a `; inherits: a,b` line, None for any other line.
+pub fn inherited_languages(line: &str) -> Option<Vec<&str>> {
+    let rest = line.trim().strip_prefix(';')?;
+    let rest = rest.trim_start_matches(';').trim_start();
+    let languages = rest.strip_prefix("inherits:")?;
+
+    Some(
+        languages
+            .split(',')
+            .map(str::trim)
+            .filter(|lang| !lang.is_empty())
+            .collect(),
+    )
+}
+
+/// Resolves the `; inherits:` lines of the query of `lang`. `read` returns the text of
+/// the same query file for a language, and fails if the language doesn't have one.
+pub fn resolve_inherits(
+    lang: &str,
+    mut read: impl FnMut(&str) -> Result<String>,
+) -> Result<String> {
+    let mut stack = vec![];
+    let mut included = vec![];
+    resolve(lang, &mut read, &mut stack, &mut included)
+}
+
+fn resolve(
+    lang: &str,
+    read: &mut impl FnMut(&str) -> Result<String>,
+    stack: &mut Vec<String>,
+    included: &mut Vec<String>,
+) -> Result<String> {
+    if stack.iter().any(|parent| parent == lang) {
+        bail!(
+            "queries inherit from each other: {} -> {lang}",
+            stack.join(" -> ")
+        );
+    }
+
+    stack.push(lang.to_string());
+    included.push(lang.to_string());
+
+    let text = read(lang)?;
+    let mut resolved = String::with_capacity(text.len());
+    for line in text.lines() {
+        match inherited_languages(line) {
+            Some(parents) => {
+                for parent in parents {
+                    if stack.iter().any(|lang| lang == parent)
+                        || !included.iter().any(|lang| lang == parent)
+                    {
+                        resolved.push_str(&resolve(parent, read, stack, included)?);
+                    }
+                }
+            }
+            None => {
+                resolved.push_str(line);
+                resolved.push('\n');
+            }
+        }
+    }
+
+    stack.pop();
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use anyhow::anyhow;
+
+    use super::*;
+
+    fn resolve_in(queries: &[(&str, &str)], lang: &str) -> Result<String> {
+        let queries: HashMap<_, _> = queries.iter().copied().collect();
+        resolve_inherits(lang, |lang| {
+            queries
+                .get(lang)
+                .map(|query| query.to_string())
+                .ok_or_else(|| anyhow!("no query for {lang}"))
+        })
+    }
+
+    #[test]
+    fn test_inherited_languages() {
+        assert_eq!(inherited_languages("; inherits: c"), Some(vec!["c"]));
+        assert_eq!(
+            inherited_languages(";; inherits: javascript, typescript"),
+            Some(vec!["javascript", "typescript"])
+        );
+        assert_eq!(inherited_languages("(comment) @comment"), None);
+        assert_eq!(inherited_languages("; This file inherits nothing"), None);
+    }
+
+    #[test]
+    fn test_resolve_inherits() {
+        let queries = [
+            ("javascript", "(js)\n"),
+            ("typescript", "(ts)\n; inherits: javascript\n"),
+            ("tsx", "(tsx)\n; inherits: typescript\n"),
+        ];
+        assert_eq!(resolve_in(&queries, "tsx").unwrap(), "(tsx)\n(ts)\n(js)\n");
+
+        // Shared parents are only included once
+        let queries = [
+            ("c", "(c)"),
+            ("cpp", "; inherits: c\n(cpp)"),
+            ("objc", "; inherits: c\n(objc)"),
+            ("objcpp", "; inherits: cpp,objc\n(objcpp)"),
+        ];
+        assert_eq!(
+            resolve_in(&queries, "objcpp").unwrap(),
+            "(c)\n(cpp)\n(objc)\n(objcpp)\n"
+        );
+
+        assert!(resolve_in(&queries, "swift").is_err());
+        assert!(resolve_in(&[("a", "; inherits: b")], "a").is_err());
+    }
+
+    #[test]
+    fn test_cycles_are_errors() {
+        let queries = [
+            ("a", "; inherits: b"),
+            ("b", "; inherits: c"),
+            ("c", "; inherits: a"),
+        ];
+        assert_eq!(
+            resolve_in(&queries, "a").unwrap_err().to_string(),
+            "queries inherit from each other: a -> b -> c -> a"
+        );
+
+        let queries = [("a", "; inherits: a")];
+        assert!(resolve_in(&queries, "a").is_err());
+    }
+}
@@ -1,2 +1,6 @@
 pub mod highlights;
+pub mod inherits;
 pub mod parsers;
+pub mod plugins;
+pub mod pool;
+pub mod validation;
@@ -1,155 +1,256 @@
-use std::collections::HashSet;
+use scip_macros::bundled_languages;
 
-use tree_sitter::Language;
+use crate::plugins::plugins;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum BundledParser {
-    C,
-    Cpp,
-    #[allow(non_camel_case_types)]
-    C_Sharp,
-    Go,
-    Java,
-    Javascript,
-    Jsonnet,
-    Kotlin,
-    Matlab,
-    Nickel,
-    Perl,
-    Pod,
-    Python,
-    Ruby,
-    Rust,
-    Scala,
-    Sql,
-    Xlsg,
-    Zig,
-
-    // These two are special cases
-    Typescript,
-    Tsx,
+// One row per bundled language, see scip_macros::bundled_languages for the format. The
+// grammar of a language is behind a cargo feature of the same name, and its queries
+// are in queries/<name>. Typescript and Tsx share a grammar crate, but are two grammars.
+//
+// TODO(SuperAuguste): language detection library
+bundled_languages! {
+    Bash {
+        language: tree_sitter_bash::language,
+        extensions: ["sh", "bash"],
+        filenames: [".bashrc", ".bash_profile"],
+        aliases: ["shell"],
+    },
+    C {
+        language: tree_sitter_c::language,
+        extensions: ["c"],
+    },
+    Cmake {
+        language: tree_sitter_cmake::language,
+        extensions: ["cmake"],
+        filenames: ["CMakeLists.txt"],
+    },
+    Clojure {
+        language: tree_sitter_clojure::language,
+        extensions: ["clj", "cljs", "cljc", "edn"],
+    },
+    Cpp {
+        language: tree_sitter_cpp::language,
+        extensions: ["cpp"],
+    },
+    C_Sharp {
+        language: tree_sitter_c_sharp::language,
+        extensions: ["cs"],
+    },
+    Css {
+        language: tree_sitter_css::language,
+        extensions: ["css"],
+    },
+    Dart {
+        language: tree_sitter_dart::language,
+        extensions: ["dart"],
+    },
+    Dockerfile {
+        language: tree_sitter_dockerfile::language,
+        extensions: ["dockerfile"],
+        filenames: ["Dockerfile", "Containerfile"],
+    },
+    Elixir {
+        language: tree_sitter_elixir::language,
+        extensions: ["ex", "exs"],
+    },
+    Erlang {
+        language: tree_sitter_erlang::language,
+        extensions: ["erl", "hrl"],
+    },
+    Go {
+        language: tree_sitter_go::language,
+        extensions: ["go"],
+    },
+    Graphql {
+        language: tree_sitter_graphql::language,
+        extensions: ["graphql", "gql"],
+    },
+    Haskell {
+        language: tree_sitter_haskell::language,
+        extensions: ["hs"],
+    },
+    Hcl {
+        language: tree_sitter_hcl::language,
+        extensions: ["hcl", "tf", "tfvars"],
+    },
+    Html {
+        language: tree_sitter_html::language,
+        extensions: ["html", "htm"],
+    },
+    Java {
+        language: tree_sitter_java::language,
+        extensions: ["java"],
+    },
+    Javascript {
+        language: tree_sitter_javascript::language,
+        extensions: ["js"],
+    },
+    Json {
+        language: tree_sitter_json::language,
+        extensions: ["json"],
+    },
+    Jsonnet {
+        language: tree_sitter_jsonnet::language,
+        extensions: ["jsonnet"],
+    },
+    Kotlin {
+        language: tree_sitter_kotlin::language,
+        extensions: ["kt"],
+    },
+    Lua {
+        language: tree_sitter_lua::language,
+        extensions: ["lua"],
+    },
+    Make {
+        language: tree_sitter_make::language,
+        extensions: ["mk", "mak"],
+        filenames: ["Makefile", "makefile", "GNUmakefile"],
+        aliases: ["makefile"],
+    },
+    Matlab {
+        language: tree_sitter_matlab::language,
+        extensions: ["m"],
+    },
+    Nickel {
+        language: tree_sitter_nickel::language,
+        extensions: ["ncl"],
+    },
+    Objc {
+        language: tree_sitter_objc::language,
+        // .m is Matlab's, so .m files are only Objective-C when go-enry says so
+        extensions: ["mm"],
+        aliases: ["objective-c"],
+    },
+    Ocaml {
+        language: tree_sitter_ocaml::language_ocaml,
+        extensions: ["ml"],
+    },
+    Perl {
+        language: tree_sitter_perl::language,
+        extensions: ["pl"],
+    },
+    Pod {
+        language: tree_sitter_pod::language,
+        extensions: ["pod"],
+    },
+    Protobuf {
+        language: tree_sitter_proto::language,
+        extensions: ["proto"],
+    },
+    Python {
+        language: tree_sitter_python::language,
+        extensions: ["py"],
+    },
+    Ruby {
+        language: tree_sitter_ruby::language,
+        extensions: ["rb"],
+    },
+    Rust {
+        language: tree_sitter_rust::language,
+        extensions: ["rs"],
+    },
+    Scala {
+        language: tree_sitter_scala::language,
+        extensions: ["scala"],
+    },
+    Scss {
+        language: tree_sitter_scss::language,
+        extensions: ["scss"],
+    },
+    Sql {
+        language: tree_sitter_sql::language,
+        extensions: ["sql"],
+    },
+    Starlark {
+        language: tree_sitter_starlark::language,
+        extensions: ["bzl", "bazel", "star"],
+        filenames: ["BUILD", "WORKSPACE", "BUCK"],
+    },
+    Svelte {
+        language: tree_sitter_svelte::language,
+        extensions: ["svelte"],
+    },
+    Swift {
+        language: tree_sitter_swift::language,
+        extensions: ["swift"],
+    },
+    Thrift {
+        language: tree_sitter_thrift::language,
+        extensions: ["thrift"],
+    },
+    Toml {
+        language: tree_sitter_toml::language,
+        extensions: ["toml"],
+    },
+    Typescript {
+        language: tree_sitter_typescript::language_typescript,
+        extensions: ["ts"],
+    },
+    Tsx {
+        language: tree_sitter_typescript::language_tsx,
+        extensions: ["tsx"],
+    },
+    Vue {
+        language: tree_sitter_vue::language,
+        extensions: ["vue"],
+    },
+    Xlsg {
+        language: tree_sitter_xlsg::language,
+        extensions: ["xlsg"],
+    },
+    Yaml {
+        language: tree_sitter_yaml::language,
+        extensions: ["yaml", "yml"],
+    },
+    Zig {
+        language: tree_sitter_zig::language,
+        extensions: ["zig"],
+    },
 }
 
 impl BundledParser {
-    pub fn get_language(self) -> Language {
-        match self {
-            BundledParser::C => tree_sitter_c::language(),
-            BundledParser::Cpp => tree_sitter_cpp::language(),
-            BundledParser::C_Sharp => tree_sitter_c_sharp::language(),
-            BundledParser::Go => tree_sitter_go::language(),
-            BundledParser::Java => tree_sitter_java::language(),
-            BundledParser::Javascript => tree_sitter_javascript::language(),
-            BundledParser::Jsonnet => tree_sitter_jsonnet::language(),
-            BundledParser::Kotlin => tree_sitter_kotlin::language(),
-            BundledParser::Matlab => tree_sitter_matlab::language(),
-            BundledParser::Nickel => tree_sitter_nickel::language(),
-            BundledParser::Perl => tree_sitter_perl::language(),
-            BundledParser::Pod => tree_sitter_pod::language(),
-            BundledParser::Python => tree_sitter_python::language(),
-            BundledParser::Ruby => tree_sitter_ruby::language(),
-            BundledParser::Rust => tree_sitter_rust::language(),
-            BundledParser::Scala => tree_sitter_scala::language(),
-            BundledParser::Sql => tree_sitter_sql::language(),
-            BundledParser::Typescript => tree_sitter_typescript::language_typescript(),
-            BundledParser::Tsx => tree_sitter_typescript::language_tsx(),
-            BundledParser::Xlsg => tree_sitter_xlsg::language(),
-            BundledParser::Zig => tree_sitter_zig::language(),
-        }
-    }
-
-    pub fn get_parser(name: &str) -> Option<Self> {
-        match name {
-            "c" => Some(BundledParser::C),
-            "cpp" => Some(BundledParser::Cpp),
-            "c_sharp" => Some(BundledParser::C_Sharp),
-            "go" => Some(BundledParser::Go),
-            "java" => Some(BundledParser::Java),
-            "javascript" => Some(BundledParser::Javascript),
-            "jsonnet" => Some(BundledParser::Jsonnet),
-            "kotlin" => Some(BundledParser::Kotlin),
-            "matlab" => Some(BundledParser::Matlab),
-            "nickel" => Some(BundledParser::Nickel),
-            "perl" => Some(BundledParser::Perl),
-            "pod" => Some(BundledParser::Pod),
-            "python" => Some(BundledParser::Python),
-            "ruby" => Some(BundledParser::Ruby),
-            "rust" => Some(BundledParser::Rust),
-            "scala" => Some(BundledParser::Scala),
-            "sql" => Some(BundledParser::Sql),
-            "typescript" => Some(BundledParser::Typescript),
-            "tsx" => Some(BundledParser::Tsx),
-            "xlsg" => Some(BundledParser::Xlsg),
-            "zig" => Some(BundledParser::Zig),
-            _ => None,
-        }
-    }
-
-    pub fn get_language_name(&self) -> &str {
-        match self {
-            BundledParser::C => "c",
-            BundledParser::Cpp => "cpp",
-            BundledParser::C_Sharp => "c_sharp",
-            BundledParser::Go => "go",
-            BundledParser::Java => "java",
-            BundledParser::Javascript => "javascript",
-            BundledParser::Jsonnet => "jsonnet",
-            BundledParser::Kotlin => "kotlin",
-            BundledParser::Matlab => "matlab",
-            BundledParser::Nickel => "nickel",
-            BundledParser::Perl => "perl",
-            BundledParser::Pod => "pod",
-            BundledParser::Python => "python",
-            BundledParser::Ruby => "ruby",
-            BundledParser::Rust => "rust",
-            BundledParser::Scala => "scala",
-            BundledParser::Sql => "sql",
-            BundledParser::Typescript => "typescript",
-            BundledParser::Tsx => "tsx",
-            BundledParser::Xlsg => "xlsg",
-            BundledParser::Zig => "zig",
-        }
+    /// Every bundled language that was compiled in, and every installed plugin.
+    pub fn available() -> Vec<Self> {
+        ALL_BUNDLED
+            .iter()
+            .copied()
+            .filter(|parser| parser.is_enabled())
+            .chain((0..plugins().len()).map(BundledParser::Plugin))
+            .collect()
     }
+}
 
-    pub fn get_language_extensions(&self) -> HashSet<&str> {
-        let ar = {
-            match self {
-                BundledParser::Go => vec!["go"],
-                BundledParser::Java => vec!["java"],
-                BundledParser::Javascript => vec!["js"],
-                BundledParser::Typescript => vec!["ts"],
-                BundledParser::Python => vec!["py"],
-                _ => vec![],
-            }
-        };
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        HashSet::from_iter(ar)
+    #[test]
+    fn test_parser_from_file_name() {
+        let parser = BundledParser::get_parser_from_file_name;
+        assert_eq!(parser("Dockerfile"), Some(BundledParser::Dockerfile));
+        assert_eq!(parser("Makefile"), Some(BundledParser::Make));
+        assert_eq!(parser("CMakeLists.txt"), Some(BundledParser::Cmake));
+        assert_eq!(parser("BUILD.bazel"), Some(BundledParser::Starlark));
+        assert_eq!(parser("BUILD"), Some(BundledParser::Starlark));
+        assert_eq!(parser("install.sh"), Some(BundledParser::Bash));
+        assert_eq!(parser("main.go"), Some(BundledParser::Go));
+        assert_eq!(parser("notes.txt"), None);
+        assert_eq!(parser("LICENSE"), None);
     }
 
-    // TODO(SuperAuguste): language detection library
-    pub fn get_parser_from_extension(name: &str) -> Option<Self> {
-        match name {
-            "c" => Some(BundledParser::C),
-            "cpp" => Some(BundledParser::Cpp),
-            "cs" => Some(BundledParser::C_Sharp),
-            "go" => Some(BundledParser::Go),
-            "java" => Some(BundledParser::Java),
-            "js" => Some(BundledParser::Javascript),
-            "jsonnet" => Some(BundledParser::Jsonnet),
-            "m" => Some(BundledParser::Matlab),
-            "kt" => Some(BundledParser::Kotlin),
-            "ncl" => Some(BundledParser::Nickel),
-            "pl" => Some(BundledParser::Perl),
-            "pod" => Some(BundledParser::Pod),
-            "py" => Some(BundledParser::Python),
-            "rb" => Some(BundledParser::Ruby),
-            "rs" => Some(BundledParser::Rust),
-            "scala" => Some(BundledParser::Scala),
-            "sql" => Some(BundledParser::Sql),
-            "ts" => Some(BundledParser::Typescript),
-            "tsx" => Some(BundledParser::Tsx),
-            "xlsg" => Some(BundledParser::Xlsg),
-            "zig" => Some(BundledParser::Zig),
-            _ => None,
-        }
+    #[test]
+    fn test_parser_from_alias() {
+        assert_eq!(
+            BundledParser::get_parser("shell"),
+            Some(BundledParser::Bash)
+        );
+        assert_eq!(
+            BundledParser::get_parser("makefile"),
+            Some(BundledParser::Make)
+        );
+        assert_eq!(
+            BundledParser::get_parser("objective-c"),
+            Some(BundledParser::Objc)
+        );
+        assert_eq!(BundledParser::Bash.get_language_name(), "bash");
     }
 }
@@ -0,0 +1,326 @@
+//! Tree-sitter grammars loaded at runtime from shared libraries.
+//!
+//! A plugin directory contains one shared library per grammar, which exports a
+//! `tree_sitter_<lang>` function like the C bindings of every grammar do, and the
+//! queries of each language next to it:
+//!
+//! ```text
+//! plugins/
+//!   lua.so                        (or libtree-sitter-lua.so)
+//!   queries/lua/highlights.scm
+//!   queries/lua/injections.scm
+//!   queries/lua/locals.scm
+//!   queries/lua/scip-tags.scm
+//!   queries/lua/scip-locals.scm
+//! ```
+//!
+//! Every query is optional, and can inherit from the same query of another plugin
+//! with `; inherits: <lang>` (see [`crate::inherits`]). Loaded plugins are available
+//! as [`BundledParser::Plugin`] through the same lookups as the bundled grammars.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use libloading::{Library, Symbol};
+use once_cell::sync::OnceCell;
+use tree_sitter::{Language, Query, LANGUAGE_VERSION, MIN_COMPATIBLE_LANGUAGE_VERSION};
+use tree_sitter_highlight::HighlightConfiguration;
+
+use crate::{highlights::highlight_names, inherits::resolve_inherits, parsers::BundledParser};
+
+static PLUGINS: OnceCell<Vec<Plugin>> = OnceCell::new();
+
+#[cfg(target_os = "macos")]
+const LIBRARY_EXTENSIONS: &[&str] = &["dylib", "so"];
+
+#[cfg(not(target_os = "macos"))]
+const LIBRARY_EXTENSIONS: &[&str] = &["so"];
+
+/// The query files of a plugin, missing files are None.
+#[derive(Debug, Default, Clone)]
+pub struct PluginQueries {
+    pub highlights: Option<String>,
+    pub injections: Option<String>,
+    pub locals: Option<String>,
+    pub scip_tags: Option<String>,
+    pub scip_locals: Option<String>,
+}
+
+pub struct Plugin {
+    pub name: &'static str,
+    pub path: PathBuf,
+    pub language: Language,
+    pub queries: PluginQueries,
+
+    // Only set when the plugin has a highlights query
+    pub highlight_configuration: Option<HighlightConfiguration>,
+
+    // The language points into the library, so it has to stay loaded as long as the
+    // plugin is around. Plugins are never unloaded once they are installed.
+    _library: Library,
+}
+
+/// A plugin that could not be loaded. Other plugins in the directory are still loaded.
+#[derive(Debug)]
+pub struct PluginError {
+    pub path: PathBuf,
+    pub error: anyhow::Error,
+}
+
+/// The result of [`load_plugins`].
+pub struct LoadedPlugins {
+    pub plugins: Vec<Plugin>,
+    pub errors: Vec<PluginError>,
+}
+
+/// Loads every grammar in `dir`. Fails only if the directory can't be read, errors
+/// of individual plugins are returned in [`LoadedPlugins::errors`].
+pub fn load_plugins(dir: &Path) -> Result<LoadedPlugins> {
+    let entries = fs::read_dir(dir).with_context(|| format!("could not read {}", dir.display()))?;
+
+    let mut paths = vec![];
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("could not read {}", dir.display()))?
+            .path();
+        let is_library = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map_or(false, |ext| LIBRARY_EXTENSIONS.contains(&ext));
+        if is_library && path.is_file() {
+            paths.push(path);
+        }
+    }
+    // Directory order is arbitrary, make it deterministic which duplicate wins
+    paths.sort();
+
+    let mut plugins: Vec<Plugin> = vec![];
+    let mut errors = vec![];
+    for path in paths {
+        let result = language_name(&path).and_then(|name| {
+            if BundledParser::get_parser(&name).is_some() {
+                bail!("{name} is already a bundled language");
+            }
+            if plugins.iter().any(|plugin| plugin.name == name) {
+                bail!("{name} is already provided by another plugin");
+            }
+
+            load_plugin(dir, &path, &name)
+        });
+
+        match result {
+            Ok(plugin) => plugins.push(plugin),
+            Err(error) => errors.push(PluginError { path, error }),
+        }
+    }
+
+    Ok(LoadedPlugins { plugins, errors })
+}
+
+/// The language name of a library, taken from its file name: `lua.so`, `liblua.so`,
+/// `libtree-sitter-lua.so` and `tree-sitter-lua.so` are all `lua`. Dashes are replaced
+/// with underscores, like in the name of the exported function.
+pub fn language_name(path: &Path) -> Result<String> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| anyhow!("invalid file name"))?;
+
+    let stem = stem.strip_prefix("lib").unwrap_or(stem);
+    let stem = stem.strip_prefix("tree-sitter-").unwrap_or(stem);
+    let name = stem.replace('-', "_").to_lowercase();
+
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        bail!("{stem:?} is not a valid language name");
+    }
+
+    Ok(name)
+}
+
+fn load_plugin(dir: &Path, path: &Path, name: &str) -> Result<Plugin> {
+    // SAFETY: loading a library runs its initializers, and we trust the libraries in
+    // the configured plugin directory like we trust our own binary.
+    let library = unsafe { Library::new(path) }.context("could not load library")?;
+
+    let symbol = format!("tree_sitter_{name}");
+    let language = unsafe {
+        let constructor: Symbol<unsafe extern "C" fn() -> Language> = library
+            .get(symbol.as_bytes())
+            .with_context(|| format!("library does not export {symbol}"))?;
+        constructor()
+    };
+
+    check_abi_version(language.version())?;
+
+    let queries = read_queries(&dir.join("queries"), name)?;
+    let highlight_configuration = highlight_configuration(language, &queries)?;
+
+    // Tags and locals are only turned into configurations when they are used, but
+    // broken queries should be reported now rather than when the first file comes in.
+    for (file, query) in [
+        ("scip-tags.scm", &queries.scip_tags),
+        ("scip-locals.scm", &queries.scip_locals),
+    ] {
+        if let Some(query) = query {
+            Query::new(language, query).with_context(|| format!("invalid {file}"))?;
+        }
+    }
+
+    Ok(Plugin {
+        // Plugins live until the process exits
+        name: Box::leak(name.to_string().into_boxed_str()),
+        path: path.to_path_buf(),
+        language,
+        queries,
+        highlight_configuration,
+        _library: library,
+    })
+}
+
+fn check_abi_version(version: usize) -> Result<()> {
+    if !(MIN_COMPATIBLE_LANGUAGE_VERSION..=LANGUAGE_VERSION).contains(&version) {
+        bail!(
+            "grammar was generated for ABI version {version}, but only versions \
+             {MIN_COMPATIBLE_LANGUAGE_VERSION} to {LANGUAGE_VERSION} are supported"
+        );
+    }
+
+    Ok(())
+}
+
+fn read_queries(dir: &Path, name: &str) -> Result<PluginQueries> {
+    let read = |file: &str| -> Result<Option<String>> {
+        if !dir.join(name).join(file).exists() {
+            return Ok(None);
+        }
+
+        resolve_inherits(name, |lang| {
+            let path = dir.join(lang).join(file);
+            fs::read_to_string(&path).with_context(|| format!("could not read {}", path.display()))
+        })
+        .map(Some)
+    };
+
+    Ok(PluginQueries {
+        highlights: read("highlights.scm")?,
+        injections: read("injections.scm")?,
+        locals: read("locals.scm")?,
+        scip_tags: read("scip-tags.scm")?,
+        scip_locals: read("scip-locals.scm")?,
+    })
+}
+
+fn highlight_configuration(
+    language: Language,
+    queries: &PluginQueries,
+) -> Result<Option<HighlightConfiguration>> {
+    let highlights = match &queries.highlights {
+        Some(highlights) => highlights,
+        None => return Ok(None),
+    };
+
+    let mut configuration = HighlightConfiguration::new(
+        language,
+        highlights,
+        queries.injections.as_deref().unwrap_or(""),
+        queries.locals.as_deref().unwrap_or(""),
+    )
+    .map_err(|err| anyhow!("invalid highlight queries: {err:?}"))?;
+    configuration.configure(&highlight_names(None));
+
+    Ok(Some(configuration))
+}
+
+/// Makes plugins available to all lookups. Must be called before the first request is
+/// highlighted.
+pub fn install_plugins(plugins: Vec<Plugin>) -> Result<()> {
+    PLUGINS
+        .set(plugins)
+        .map_err(|_| anyhow!("plugins have already been initialized"))
+}
+
+/// The installed plugins. The index of a plugin is its id in [`BundledParser::Plugin`].
+pub fn plugins() -> &'static [Plugin] {
+    // Not get_or_init: lookups happen while plugins are loaded, before they are installed
+    PLUGINS.get().map_or(&[], |plugins| plugins.as_slice())
+}
+
+pub fn get_plugin(id: usize) -> Option<&'static Plugin> {
+    plugins().get(id)
+}
+
+pub fn find_plugin(name: &str) -> Option<usize> {
+    plugins().iter().position(|plugin| plugin.name == name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_language_name() {
+        let name = |path: &str| language_name(Path::new(path)).ok();
+
+        assert_eq!(name("plugins/lua.so"), Some("lua".to_string()));
+        assert_eq!(name("liblua.so"), Some("lua".to_string()));
+        assert_eq!(
+            name("libtree-sitter-common-lisp.so"),
+            Some("common_lisp".to_string())
+        );
+        assert_eq!(name("tree-sitter-Lua.dylib"), Some("lua".to_string()));
+        assert_eq!(name("lib.so"), None);
+        assert_eq!(name("lua.v2.so"), None);
+    }
+
+    #[test]
+    fn test_abi_version() {
+        assert!(check_abi_version(LANGUAGE_VERSION).is_ok());
+        assert!(check_abi_version(MIN_COMPATIBLE_LANGUAGE_VERSION).is_ok());
+        assert!(check_abi_version(LANGUAGE_VERSION + 1).is_err());
+        assert!(check_abi_version(MIN_COMPATIBLE_LANGUAGE_VERSION - 1).is_err());
+    }
+
+    #[test]
+    fn test_errors_are_reported_per_plugin() {
+        let dir = std::env::temp_dir().join(format!("grammar-plugins-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("notalibrary.so"), "not a shared library").unwrap();
+        fs::write(dir.join("go.so"), "").unwrap();
+        fs::write(dir.join("README.md"), "").unwrap();
+
+        let loaded = load_plugins(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(loaded.plugins.is_empty());
+        let mut errors: Vec<_> = loaded
+            .errors
+            .iter()
+            .map(|err| {
+                (
+                    err.path.file_name().unwrap().to_str().unwrap().to_string(),
+                    err.error.to_string(),
+                )
+            })
+            .collect();
+        errors.sort();
+
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    "go.so".to_string(),
+                    "go is already a bundled language".to_string()
+                ),
+                (
+                    "notalibrary.so".to_string(),
+                    "could not load library".to_string()
+                ),
+            ]
+        );
+
+        assert!(load_plugins(&dir).is_err());
+    }
+}
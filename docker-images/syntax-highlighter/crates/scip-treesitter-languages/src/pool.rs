@@ -0,0 +1,195 @@
+//! Per-thread reuse of parsers, query cursors and highlighters.
+//!
+//! Creating these is cheap compared to parsing a large file, but it adds up when a lot
+//! of small files are indexed or highlighted one after another: every one of them
+//! allocates its parse stack, cursor states and so on from scratch. The guards returned
+//! here hand out an idle instance of the current thread if there is one, and give it
+//! back when they are dropped.
+//!
+//! Settings that outlive a single use (timeouts, included ranges, match limits, ...)
+//! must not be changed on pooled instances, create a new one for that instead.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    thread::LocalKey,
+};
+
+use tree_sitter::{Parser, QueryCursor};
+use tree_sitter_highlight::Highlighter;
+
+use crate::parsers::BundledParser;
+
+// Instances beyond this many are dropped when they are given back. Unless pooled
+// instances are used recursively, one per thread (and language) is enough.
+const MAX_IDLE: usize = 4;
+
+thread_local! {
+    static PARSERS: RefCell<HashMap<BundledParser, Vec<Parser>>> = RefCell::new(HashMap::new());
+    static QUERY_CURSORS: RefCell<Vec<QueryCursor>> = RefCell::new(vec![]);
+    static HIGHLIGHTERS: RefCell<Vec<Highlighter>> = RefCell::new(vec![]);
+}
+
+/// A parser for `language`, which goes back to the pool when it is dropped.
+pub fn parser(language: BundledParser) -> PooledParser {
+    let idle = PARSERS
+        .try_with(|parsers| parsers.borrow_mut().get_mut(&language)?.pop())
+        .ok()
+        .flatten();
+
+    let parser = idle.unwrap_or_else(|| {
+        let mut parser = Parser::new();
+        parser
+            .set_language(language.get_language())
+            .expect("bundled languages and plugins are compatible with tree-sitter");
+        parser
+    });
+
+    PooledParser {
+        parser: Some(parser),
+        language,
+    }
+}
+
+/// A query cursor, which goes back to the pool when it is dropped. Cursors work with
+/// any query, so they aren't kept per language.
+pub fn query_cursor() -> Pooled<QueryCursor> {
+    Pooled::take(&QUERY_CURSORS, QueryCursor::new)
+}
+
+/// A highlighter, which goes back to the pool when it is dropped. Highlighters work
+/// with any `HighlightConfiguration`, and keep a parser and cursors for them inside.
+pub fn highlighter() -> Pooled<Highlighter> {
+    Pooled::take(&HIGHLIGHTERS, Highlighter::new)
+}
+
+pub struct PooledParser {
+    // Only None while it is given back
+    parser: Option<Parser>,
+    language: BundledParser,
+}
+
+impl Deref for PooledParser {
+    type Target = Parser;
+
+    fn deref(&self) -> &Parser {
+        self.parser.as_ref().expect("parser is set until dropped")
+    }
+}
+
+impl DerefMut for PooledParser {
+    fn deref_mut(&mut self) -> &mut Parser {
+        self.parser.as_mut().expect("parser is set until dropped")
+    }
+}
+
+impl Drop for PooledParser {
+    fn drop(&mut self) {
+        let Some(mut parser) = self.parser.take() else {
+            return;
+        };
+
+        // A parse that timed out or was cancelled would otherwise be resumed by the
+        // next parse, with a different text
+        parser.reset();
+
+        // Fails only while the thread is shutting down, then the parser is just dropped
+        let _ = PARSERS.try_with(|parsers| {
+            let mut parsers = parsers.borrow_mut();
+            let idle = parsers.entry(self.language).or_default();
+            if idle.len() < MAX_IDLE {
+                idle.push(parser);
+            }
+        });
+    }
+}
+
+pub struct Pooled<T: 'static> {
+    // Only None while it is given back
+    item: Option<T>,
+    pool: &'static LocalKey<RefCell<Vec<T>>>,
+}
+
+impl<T: 'static> Pooled<T> {
+    fn take(pool: &'static LocalKey<RefCell<Vec<T>>>, new: fn() -> T) -> Self {
+        let idle = pool.try_with(|pool| pool.borrow_mut().pop()).ok().flatten();
+        Pooled {
+            item: Some(idle.unwrap_or_else(new)),
+            pool,
+        }
+    }
+}
+
+impl<T: 'static> Deref for Pooled<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item.as_ref().expect("item is set until dropped")
+    }
+}
+
+impl<T: 'static> DerefMut for Pooled<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.item.as_mut().expect("item is set until dropped")
+    }
+}
+
+impl<T: 'static> Drop for Pooled<T> {
+    fn drop(&mut self) {
+        let Some(item) = self.item.take() else {
+            return;
+        };
+
+        let _ = self.pool.try_with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.len() < MAX_IDLE {
+                pool.push(item);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn idle_parsers(language: BundledParser) -> usize {
+        PARSERS.with(|parsers| parsers.borrow().get(&language).map_or(0, Vec::len))
+    }
+
+    #[test]
+    fn test_parsers_are_reused_per_language() {
+        let mut go = parser(BundledParser::Go);
+        assert!(go.parse("package main", None).is_some());
+        drop(go);
+        assert_eq!(idle_parsers(BundledParser::Go), 1);
+
+        let go = parser(BundledParser::Go);
+        assert_eq!(idle_parsers(BundledParser::Go), 0);
+        assert_eq!(go.language(), Some(BundledParser::Go.get_language()));
+
+        // Idle parsers of other languages are not used
+        let python = parser(BundledParser::Python);
+        assert_eq!(
+            python.language(),
+            Some(BundledParser::Python.get_language())
+        );
+
+        // Nor are parsers that are still in use
+        let second = parser(BundledParser::Go);
+        drop(go);
+        drop(second);
+        assert_eq!(idle_parsers(BundledParser::Go), 2);
+    }
+
+    #[test]
+    fn test_idle_instances_are_limited() {
+        let cursors: Vec<_> = (0..MAX_IDLE + 2).map(|_| query_cursor()).collect();
+        drop(cursors);
+        QUERY_CURSORS.with(|cursors| assert_eq!(cursors.borrow().len(), MAX_IDLE));
+
+        let _cursor = query_cursor();
+        QUERY_CURSORS.with(|cursors| assert_eq!(cursors.borrow().len(), MAX_IDLE - 1));
+    }
+}
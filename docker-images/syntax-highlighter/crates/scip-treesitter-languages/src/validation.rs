@@ -0,0 +1,343 @@
+//! Checks for query files that go beyond compiling them.
+//!
+//! A query that compiles can still be wrong in ways that tree-sitter doesn't tell us
+//! about: captures that nothing reads are silently dropped, and predicates that neither
+//! tree-sitter nor our code implement always succeed. The tests of this crate and of
+//! `scip-syntax` run [`validate_query_directory`] on their bundled query files.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use tree_sitter::{CaptureQuantifier, Query};
+
+use crate::{
+    highlights::highlight_names,
+    inherits::resolve_inherits,
+    parsers::{BundledParser, ALL_BUNDLED},
+};
+
+/// The query files we know how to read, named after their file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    Highlights,
+    Injections,
+    Locals,
+    ScipTags,
+    ScipReferences,
+    ScipLocals,
+}
+
+impl QueryKind {
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        match file_name {
+            "highlights.scm" => Some(QueryKind::Highlights),
+            "injections.scm" => Some(QueryKind::Injections),
+            "locals.scm" => Some(QueryKind::Locals),
+            "scip-tags.scm" => Some(QueryKind::ScipTags),
+            "scip-references.scm" => Some(QueryKind::ScipReferences),
+            "scip-locals.scm" => Some(QueryKind::ScipLocals),
+            _ => None,
+        }
+    }
+
+    // Captures starting with an underscore are only used in predicates, or to keep a
+    // node from being highlighted by a later pattern.
+    fn check_capture(self, parser: BundledParser, name: &str) -> Option<String> {
+        if name.starts_with('_') {
+            return None;
+        }
+
+        let known = match self {
+            QueryKind::Highlights => highlight_for_capture(parser, name).is_some(),
+            QueryKind::Injections => {
+                matches!(name, "injection.content" | "injection.language")
+            }
+            QueryKind::Locals => matches!(
+                name,
+                "local.scope" | "local.definition" | "local.definition-value" | "local.reference"
+            ),
+            // See scip_syntax::globals and scip_syntax::symbols
+            QueryKind::ScipTags | QueryKind::ScipReferences => has_prefix(
+                name,
+                &[
+                    "descriptor",
+                    "scope",
+                    "enclosing",
+                    "local",
+                    "kind",
+                    "arity",
+                    "reference",
+                    "injection",
+                ],
+            ),
+            // See scip_syntax::locals
+            QueryKind::ScipLocals => has_prefix(name, &["definition", "reference", "scope"]),
+        };
+
+        if known {
+            None
+        } else if self == QueryKind::Highlights {
+            Some(format!("@{name} doesn't map to a SyntaxKind"))
+        } else {
+            Some(format!("@{name} is not a known {self:?} capture"))
+        }
+    }
+
+    fn check_general_predicate(self, operator: &str) -> Option<String> {
+        let supported = match self {
+            // See scip_syntax::languages::TagConfiguration
+            QueryKind::ScipTags | QueryKind::ScipReferences => {
+                matches!(operator, "transform!" | "filter!")
+            }
+            _ => false,
+        };
+
+        if supported {
+            None
+        } else {
+            Some(format!("#{operator} is not supported in {self:?} queries"))
+        }
+    }
+
+    fn check_property(self, key: &str, value: Option<&str>) -> Option<String> {
+        let supported = match self {
+            QueryKind::Injections => matches!(
+                key,
+                "injection.language" | "injection.combined" | "injection.include-children"
+            ),
+            QueryKind::Locals => key == "local.scope-inherits",
+            // Tagged with the tags of that language, see scip_syntax::languages::Injections
+            QueryKind::ScipTags => key == "injection.language",
+            // scip_syntax::locals panics on any other value
+            QueryKind::ScipLocals => matches!(
+                (key, value),
+                ("scope", Some("global" | "parent" | "local"))
+                    | (
+                        "reassignment_behavior",
+                        Some("newest_is_definition" | "oldest_is_definition")
+                    )
+            ),
+            QueryKind::Highlights | QueryKind::ScipReferences => false,
+        };
+
+        if supported {
+            None
+        } else {
+            Some(format!(
+                "#set! {key} {} is not supported in {self:?} queries",
+                value.unwrap_or("")
+            ))
+        }
+    }
+
+    // tree-sitter-highlight checks `#is? local` and `#is-not? local` in highlights,
+    // nothing else reads property predicates.
+    fn check_property_predicate(self, key: &str, is_positive: bool) -> Option<String> {
+        if self == QueryKind::Highlights && key == "local" {
+            return None;
+        }
+
+        let operator = if is_positive { "is?" } else { "is-not?" };
+        Some(format!(
+            "#{operator} {key} is not supported in {self:?} queries"
+        ))
+    }
+}
+
+fn has_prefix(name: &str, prefixes: &[&str]) -> bool {
+    let first = name.split('.').next().unwrap_or(name);
+    prefixes.contains(&first)
+}
+
+/// The index in the highlight names of `parser` that a capture is highlighted as, using
+/// the same rule as `HighlightConfiguration::configure`: the first of the longest
+/// highlight names whose parts all appear in the capture name, e.g.
+/// `keyword.return.special` is `keyword.return`.
+pub fn highlight_for_capture(parser: BundledParser, capture: &str) -> Option<usize> {
+    let capture_parts: Vec<&str> = capture.split('.').collect();
+
+    let mut best = None;
+    let mut best_len = 0;
+    for (idx, name) in highlight_names(Some(parser)).iter().enumerate() {
+        let parts: Vec<&str> = name.split('.').collect();
+        if parts.len() > best_len && parts.iter().all(|part| capture_parts.contains(part)) {
+            best = Some(idx);
+            best_len = parts.len();
+        }
+    }
+
+    best
+}
+
+/// Compiles `source` and returns every problem in it, prefixed with its line. Fails if
+/// the query doesn't compile.
+pub fn validate_query(parser: BundledParser, kind: QueryKind, source: &str) -> Result<Vec<String>> {
+    let query = Query::new(parser.get_language(), source).map_err(|err| anyhow!("{err}"))?;
+
+    let mut problems = vec![];
+    let capture_names = query.capture_names();
+    let mut reported_captures = vec![false; capture_names.len()];
+
+    for pattern in 0..query.pattern_count() {
+        let line = source[..query.start_byte_for_pattern(pattern)]
+            .matches('\n')
+            .count()
+            + 1;
+        let mut report = |problem: Option<String>| {
+            if let Some(problem) = problem {
+                problems.push(format!("line {line}: {problem}"));
+            }
+        };
+
+        for predicate in query.general_predicates(pattern) {
+            report(kind.check_general_predicate(&predicate.operator));
+        }
+
+        for property in query.property_settings(pattern) {
+            report(kind.check_property(&property.key, property.value.as_deref()));
+        }
+
+        for (property, is_positive) in query.property_predicates(pattern) {
+            report(kind.check_property_predicate(&property.key, *is_positive));
+        }
+
+        // Every capture is reported once, on the first pattern that uses it
+        for (idx, name) in capture_names.iter().enumerate() {
+            if reported_captures[idx] || !pattern_has_capture(&query, pattern, idx) {
+                continue;
+            }
+
+            reported_captures[idx] = true;
+            report(kind.check_capture(parser, name));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Validates the query files of every enabled language in `queries`, which has a
+/// directory per language named like the language, e.g. `queries/go/highlights.scm`.
+/// `; inherits:` lines are resolved from the same directory.
+///
+/// `check` is called with every query that compiles, for checks that only the crate
+/// reading the queries can do. Returns the sorted problems, prefixed with the file.
+pub fn validate_query_directory(
+    queries: &Path,
+    mut check: impl FnMut(BundledParser, QueryKind, &Query) -> Vec<String>,
+) -> Result<Vec<String>> {
+    let mut problems = vec![];
+    for entry in std::fs::read_dir(queries)? {
+        let dir = entry?.path();
+        let name = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("invalid directory {}", dir.display()))?;
+        let Some(parser) = ALL_BUNDLED
+            .iter()
+            .copied()
+            .find(|parser| parser.get_language_name() == name)
+        else {
+            problems.push(format!("{name}: is not a bundled language"));
+            continue;
+        };
+        if !parser.is_enabled() {
+            continue;
+        }
+
+        for file in std::fs::read_dir(&dir)? {
+            let path = file?.path();
+            let file_name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| anyhow!("invalid file {}", path.display()))?;
+            if file_name == "syntax-kinds.txt" {
+                continue;
+            }
+
+            let Some(kind) = QueryKind::from_file_name(file_name) else {
+                problems.push(format!("{name}/{file_name}: unknown query file"));
+                continue;
+            };
+
+            let source = resolve_inherits(name, |lang| {
+                Ok(std::fs::read_to_string(queries.join(lang).join(file_name))?)
+            });
+            let found = source.and_then(|source| {
+                let mut found = validate_query(parser, kind, &source)?;
+                let query = Query::new(parser.get_language(), &source)?;
+                found.extend(check(parser, kind, &query));
+                Ok(found)
+            });
+
+            match found {
+                Ok(found) => {
+                    problems.extend(found.into_iter().map(|p| format!("{name}/{file_name} {p}")))
+                }
+                Err(err) => problems.push(format!("{name}/{file_name}: {err}")),
+            }
+        }
+    }
+
+    problems.sort();
+    Ok(problems)
+}
+
+fn pattern_has_capture(query: &Query, pattern: usize, capture: usize) -> bool {
+    // Patterns don't expose their captures, but a quantifier is recorded for every
+    // capture of every pattern, and it is Zero for captures the pattern doesn't have.
+    query.capture_quantifiers(pattern)[capture] != CaptureQuantifier::Zero
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_highlight_for_capture() {
+        let names = highlight_names(Some(BundledParser::Go));
+        let name =
+            |capture| highlight_for_capture(BundledParser::Go, capture).map(|idx| names[idx]);
+
+        assert_eq!(name("keyword"), Some("keyword"));
+        assert_eq!(name("keyword.repeat"), Some("keyword"));
+        assert_eq!(name("function.builtin.static"), Some("function.builtin"));
+        assert_eq!(name("function.macro"), Some("function"));
+        assert_eq!(name("spell"), None);
+
+        // Rust has an overlay for macros
+        let rust = highlight_names(Some(BundledParser::Rust));
+        let idx = highlight_for_capture(BundledParser::Rust, "function.macro").unwrap();
+        assert_eq!(rust[idx], "function.macro");
+    }
+
+    #[test]
+    fn test_problems_are_reported() {
+        let parser = BundledParser::Go;
+        let query = r#"
+(comment) @comment @spell
+((identifier) @constant (#any-of? @constant "a" "b"))
+(identifier) @_skipped
+"#;
+
+        let problems = validate_query(parser, QueryKind::Highlights, query).unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                "line 2: @spell doesn't map to a SyntaxKind",
+                "line 3: #any-of? is not supported in Highlights queries",
+            ]
+        );
+
+        assert!(validate_query(parser, QueryKind::Highlights, "(no_such_node) @x").is_err());
+    }
+
+    #[test]
+    fn test_bundled_queries() {
+        let queries: PathBuf = [env!("CARGO_MANIFEST_DIR"), "queries"].iter().collect();
+
+        let problems = validate_query_directory(&queries, |_, _, _| vec![]).unwrap();
+        assert!(problems.is_empty(), "{}", problems.join("\n"));
+    }
+}
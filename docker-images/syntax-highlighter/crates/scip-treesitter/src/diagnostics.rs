@@ -0,0 +1,137 @@
+use scip::types::{Diagnostic, Occurrence, Severity};
+use tree_sitter::Node;
+
+use crate::NodeToScipRange;
+
+/// Used as the `source` of every diagnostic we emit, so that clients can tell
+/// them apart from diagnostics reported by compilers or linters.
+pub const DIAGNOSTIC_SOURCE: &str = "tree-sitter";
+
+// Longest snippet of unexpected source text we quote in a diagnostic message.
+const MAX_SNIPPET_CHARS: usize = 40;
+
+/// An `ERROR` or `MISSING` node that tree-sitter produced while recovering from
+/// a syntax error.
+pub struct SyntaxError<'a> {
+    pub node: Node<'a>,
+    pub message: String,
+}
+
+impl<'a> SyntaxError<'a> {
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error.into(),
+            code: if self.node.is_missing() {
+                "missing-node".to_string()
+            } else {
+                "syntax-error".to_string()
+            },
+            message: self.message.clone(),
+            source: DIAGNOSTIC_SOURCE.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Occurrence covering the node and carrying its diagnostic.
+    ///
+    /// The range uses tree-sitter's byte based columns, callers that work with
+    /// character based columns should build the occurrence themselves.
+    pub fn to_occurrence(&self) -> Occurrence {
+        Occurrence {
+            range: self.node.to_scip_range(),
+            diagnostics: vec![self.to_diagnostic()],
+            ..Default::default()
+        }
+    }
+}
+
+/// Collects the outermost `ERROR` nodes and all `MISSING` nodes below `root`,
+/// in document order. Nodes nested inside of an `ERROR` node are not reported
+/// separately.
+pub fn syntax_errors<'a>(root: Node<'a>, source: &[u8]) -> Vec<SyntaxError<'a>> {
+    let mut errors = vec![];
+    if !root.has_error() {
+        return errors;
+    }
+
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        let descend = if node.is_error() || node.is_missing() {
+            errors.push(SyntaxError {
+                node,
+                message: error_message(node, source),
+            });
+            false
+        } else {
+            // has_error is also true for subtrees containing MISSING nodes
+            node.has_error()
+        };
+
+        if descend && cursor.goto_first_child() {
+            continue;
+        }
+
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+
+            if !cursor.goto_parent() {
+                return errors;
+            }
+        }
+    }
+}
+
+fn error_message(node: Node, source: &[u8]) -> String {
+    if node.is_missing() {
+        return match node.parent() {
+            Some(parent) => format!("missing {} in {}", describe_kind(node), parent.kind()),
+            None => format!("missing {}", describe_kind(node)),
+        };
+    }
+
+    let text = node.utf8_text(source).unwrap_or_default();
+    let first_line = text.lines().next().unwrap_or_default().trim();
+    let mut snippet: String = first_line.chars().take(MAX_SNIPPET_CHARS).collect();
+    if snippet.len() < text.trim().len() {
+        snippet.push_str("...");
+    }
+
+    match (node.parent(), expected_after(node)) {
+        (Some(parent), Some(expected)) => format!(
+            "unexpected {snippet:?} in {}, expected {}",
+            parent.kind(),
+            describe_kind(expected)
+        ),
+        (Some(parent), None) => format!("unexpected {snippet:?} in {}", parent.kind()),
+        (None, _) => format!("unexpected {snippet:?}"),
+    }
+}
+
+// tree-sitter 0.20 doesn't expose parse states, so the symbols that were valid at an
+// ERROR node can't be looked up. The parser skipped the ERROR to resume with its next
+// sibling though, which is what the parent's grammar expected at that point, e.g. the
+// expression_list of a return_statement in `return ) 1`.
+fn expected_after(node: Node) -> Option<Node> {
+    let mut sibling = node.next_sibling();
+    while let Some(next) = sibling {
+        if !next.is_extra() && !next.is_error() && !next.is_missing() {
+            return Some(next);
+        }
+        sibling = next.next_sibling();
+    }
+
+    None
+}
+
+// Named nodes are described by their kind ("identifier"), anonymous nodes
+// by the quoted token they stand for ("\";\"").
+fn describe_kind(node: Node) -> String {
+    if node.is_named() {
+        node.kind().to_string()
+    } else {
+        format!("{:?}", node.kind())
+    }
+}
@@ -1,5 +1,6 @@
 use tree_sitter::Node;
 
+pub mod diagnostics;
 pub mod snapshot;
 pub mod types;
 
@@ -0,0 +1,272 @@
+//! Cheap checks on the contents of a file that tell us whether highlighting it is
+//! worth it, and that explain to users why a file doesn't look like regular code.
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+
+static SKIP_POLICY: OnceCell<SkipPolicy> = OnceCell::new();
+
+// Only this much of a file is looked at to decide whether it is binary, like git does.
+const BINARY_SAMPLE_LEN: usize = 8000;
+
+// Fraction of control characters above which text is considered binary.
+const BINARY_MAX_CONTROL_RATIO: f64 = 0.1;
+
+// Small files are cheap to highlight, even when minified.
+const MINIFIED_MIN_LEN: usize = 1024;
+
+const MINIFIED_MIN_AVG_LINE_LEN: usize = 300;
+
+// Fraction of whitespace below which long lines are considered minified. Regular code
+// and prose are usually above 15%, because of indentation and spaces between words.
+const MINIFIED_MAX_WHITESPACE_RATIO: f64 = 0.1;
+
+// Generated code markers are put at the top of a file, after a license header at most.
+const GENERATED_HEADER_LINES: usize = 30;
+
+/// The result of classifying the contents of a file, returned as `flags` with every
+/// highlight and symbols response.
+#[derive(Serialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentFlags {
+    pub minified: bool,
+    pub generated: bool,
+    pub binary: bool,
+    pub lockfile: bool,
+}
+
+pub fn classify(filepath: &str, code: &str) -> ContentFlags {
+    let path = Path::new(filepath);
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    let binary = is_binary(code);
+    ContentFlags {
+        // The heuristics below make no sense for binary content
+        minified: !binary && is_minified(file_name, code),
+        generated: !binary && is_generated(file_name, code),
+        binary,
+        lockfile: is_lockfile(file_name),
+    }
+}
+
+fn is_binary(code: &str) -> bool {
+    let mut total = 0;
+    let mut control = 0;
+    for c in code.chars().take(BINARY_SAMPLE_LEN) {
+        if c == '\0' {
+            return true;
+        }
+
+        total += 1;
+        if (c.is_control() && !c.is_whitespace()) || c == char::REPLACEMENT_CHARACTER {
+            control += 1;
+        }
+    }
+
+    total > 0 && control as f64 / total as f64 > BINARY_MAX_CONTROL_RATIO
+}
+
+fn is_minified(file_name: &str, code: &str) -> bool {
+    if file_name.contains(".min.") {
+        return true;
+    }
+
+    // Notebooks store outputs (e.g. base64 images) on a single line, which says
+    // nothing about the code in their cells.
+    if file_name.ends_with(".ipynb") || code.len() < MINIFIED_MIN_LEN {
+        return false;
+    }
+
+    let line_count = code.lines().count().max(1);
+    if code.len() / line_count < MINIFIED_MIN_AVG_LINE_LEN {
+        return false;
+    }
+
+    let whitespace = code.chars().filter(|c| c.is_whitespace()).count();
+    (whitespace as f64 / code.chars().count() as f64) < MINIFIED_MAX_WHITESPACE_RATIO
+}
+
+// Markers that tools put at the top of files they generate.
+fn is_generated_marker(line: &str) -> bool {
+    let line = line.trim();
+
+    // Go: https://pkg.go.dev/cmd/go#hdr-Generate_Go_files_by_processing_source
+    if line.starts_with("// Code generated ") && line.ends_with(" DO NOT EDIT.") {
+        return true;
+    }
+
+    [
+        // Used by Buck, Rust's protobuf and many others
+        "@generated",
+        // C# and Visual Studio
+        "<auto-generated",
+        // protoc for C++, Python, Java and others
+        "Generated by the protocol buffer compiler.",
+        "Autogenerated by Thrift Compiler",
+        // Dart build_runner
+        "GENERATED CODE - DO NOT MODIFY BY HAND",
+        // Cython
+        "Generated by Cython",
+    ]
+    .iter()
+    .any(|marker| line.contains(marker))
+}
+
+fn is_generated(file_name: &str, code: &str) -> bool {
+    const GENERATED_SUFFIXES: &[&str] = &[
+        ".pb.go",
+        ".pb.cc",
+        ".pb.h",
+        "_pb2.py",
+        "_pb2_grpc.py",
+        ".g.dart",
+        ".freezed.dart",
+        ".designer.cs",
+        ".g.cs",
+    ];
+
+    GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+        || code
+            .lines()
+            .take(GENERATED_HEADER_LINES)
+            .any(is_generated_marker)
+}
+
+fn is_lockfile(file_name: &str) -> bool {
+    matches!(
+        file_name,
+        "Cargo.lock"
+            | "package-lock.json"
+            | "npm-shrinkwrap.json"
+            | "yarn.lock"
+            | "pnpm-lock.yaml"
+            | "bun.lockb"
+            | "Gemfile.lock"
+            | "Pipfile.lock"
+            | "poetry.lock"
+            | "composer.lock"
+            | "go.sum"
+            | "mix.lock"
+            | "flake.lock"
+            | "Podfile.lock"
+            | "pubspec.lock"
+            | "packages.lock.json"
+    )
+}
+
+/// Which kinds of content are not highlighted at all. Skipped files are returned as
+/// plain text, with their flags set.
+///
+/// Nothing is skipped unless SKIP_HIGHLIGHTING says so, so that clients that don't know
+/// about flags get the same responses as before.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SkipPolicy {
+    pub minified: bool,
+    pub generated: bool,
+    pub binary: bool,
+    pub lockfile: bool,
+}
+
+impl SkipPolicy {
+    /// Parses a comma separated list like `minified,binary`. An empty list disables
+    /// skipping.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut policy = SkipPolicy::default();
+
+        for kind in spec.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            match kind {
+                "minified" => policy.minified = true,
+                "generated" => policy.generated = true,
+                "binary" => policy.binary = true,
+                "lockfile" => policy.lockfile = true,
+                _ => bail!(
+                    "unknown content kind {kind:?}, expected minified, generated, binary or lockfile"
+                ),
+            }
+        }
+
+        Ok(policy)
+    }
+
+    pub fn should_skip(&self, flags: &ContentFlags) -> bool {
+        (self.minified && flags.minified)
+            || (self.generated && flags.generated)
+            || (self.binary && flags.binary)
+            || (self.lockfile && flags.lockfile)
+    }
+}
+
+/// Replaces the default policy. Must be called before the first request is highlighted.
+pub fn install_skip_policy(policy: SkipPolicy) -> Result<()> {
+    SKIP_POLICY
+        .set(policy)
+        .map_err(|_| anyhow!("skip policy has already been initialized"))
+}
+
+pub fn skip_policy() -> &'static SkipPolicy {
+    SKIP_POLICY.get_or_init(SkipPolicy::default)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_regular_code() {
+        let code = "package main\n\nfunc main() {\n\tprintln(\"hi\")\n}\n".repeat(100);
+        assert_eq!(classify("main.go", &code), ContentFlags::default());
+    }
+
+    #[test]
+    fn test_minified() {
+        let line = "function a(b){return b+1};var c=a(2);".repeat(100);
+        assert!(classify("bundle.js", &line).minified);
+        assert!(classify("jquery.min.js", "").minified);
+
+        // Long, but regularly indented lines are fine
+        let prose = "word ".repeat(1000);
+        assert!(!classify("notes.txt", &prose).minified);
+    }
+
+    #[test]
+    fn test_generated() {
+        let go = "// Code generated by protoc-gen-go. DO NOT EDIT.\n\npackage foo\n";
+        assert!(classify("foo.go", go).generated);
+        assert!(classify("foo_pb2.py", "").generated);
+        assert!(classify("Foo.cs", "// <auto-generated>\nclass Foo {}\n").generated);
+
+        // The Go marker has to be the whole line
+        let go = "// Code generated by hand, please edit.\n";
+        assert!(!classify("foo.go", go).generated);
+    }
+
+    #[test]
+    fn test_binary_and_lockfiles() {
+        assert!(classify("image.png", "\u{89}PNG\r\n\u{1a}\n\0\0\0").binary);
+        assert!(!classify("main.go", "package main\n").binary);
+
+        assert!(classify("vendor/Cargo.lock", "").lockfile);
+        assert!(!classify("Cargo.toml", "").lockfile);
+    }
+
+    #[test]
+    fn test_skip_policy() {
+        let policy = SkipPolicy::parse("minified, lockfile").unwrap();
+        assert!(policy.should_skip(&ContentFlags {
+            minified: true,
+            ..Default::default()
+        }));
+        assert!(!policy.should_skip(&ContentFlags {
+            binary: true,
+            ..Default::default()
+        }));
+
+        assert!(!SkipPolicy::parse("").unwrap().binary);
+        assert_eq!(SkipPolicy::default(), SkipPolicy::parse("").unwrap());
+        assert!(SkipPolicy::parse("huge").is_err());
+    }
+}
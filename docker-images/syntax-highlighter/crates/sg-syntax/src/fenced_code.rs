@@ -0,0 +1,491 @@
+//! Highlighting of fenced code blocks (```` ```lang ````) inside of Markdown files and
+//! doc comments.
+//!
+//! Blocks are found with a line based scan, highlighted on their own as the language
+//! named by the fence, and the resulting occurrences are moved to their position in the
+//! outer document.
+
+use scip::types::{Document, Occurrence};
+use scip_treesitter::types::PackedRange;
+use scip_treesitter_languages::parsers::BundledParser;
+
+use crate::sg_treesitter::{end_of, range_between, start_of};
+
+// Attributes that rustdoc accepts in place of (or next to) a language name. A block with
+// only these is Rust code.
+const RUSTDOC_ATTRIBUTES: &[&str] = &[
+    "ignore",
+    "no_run",
+    "should_panic",
+    "compile_fail",
+    "edition2015",
+    "edition2018",
+    "edition2021",
+];
+
+/// A line of a fenced code block. `column` is the character offset in the outer document
+/// line at which `text` starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BlockLine {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FencedBlock {
+    // The info string of the opening fence, e.g. `rust,ignore`
+    pub info: String,
+
+    // The lines between the fences
+    pub lines: Vec<BlockLine>,
+}
+
+impl FencedBlock {
+    pub fn code(&self) -> String {
+        let mut code = String::new();
+        for line in &self.lines {
+            code += &line.text;
+            code += "\n";
+        }
+        code
+    }
+
+    /// The language named by the info string, e.g. `rust` for `rust,ignore`. `default`
+    /// is used for blocks without a language.
+    pub fn language(&self, default: Option<&str>) -> Option<String> {
+        let first = self
+            .info
+            .split(|c: char| c == ',' || c == '{' || c == '}' || c.is_whitespace())
+            .find(|token| !token.is_empty());
+
+        match first {
+            None => default.map(str::to_string),
+            Some(token) if default == Some("rust") && RUSTDOC_ATTRIBUTES.contains(&token) => {
+                Some("rust".to_string())
+            }
+            Some(token) => Some(token.to_lowercase()),
+        }
+    }
+
+    /// The tree-sitter parser for the language of the block, if we bundle one.
+    pub fn parser(&self, default: Option<&str>) -> Option<BundledParser> {
+        let language = self.language(default)?;
+
+        // Names that are common in fences, but that are neither a parser name nor an
+        // extension we know about.
+        let language = match language.as_str() {
+            "golang" => "go",
+            "c++" | "cxx" | "hpp" => "cpp",
+            "csharp" | "c#" => "c_sharp",
+            "jsx" | "mjs" => "javascript",
+            "python3" => "python",
+            "terraform" => "hcl",
+            other => other,
+        };
+
+        BundledParser::get_parser(language)
+            .or_else(|| BundledParser::get_parser_from_extension(language))
+    }
+
+    /// The range of the block's contents in the outer document.
+    pub fn content_range(&self) -> Option<PackedRange> {
+        let first = self.lines.first()?;
+        let last = self.lines.last()?;
+        Some(PackedRange {
+            start_line: first.line as i32,
+            start_col: first.column as i32,
+            end_line: last.line as i32,
+            end_col: (last.column + last.text.chars().count()) as i32,
+        })
+    }
+
+    /// Moves the occurrences of the highlighted block contents to the outer document.
+    /// Occurrences spanning multiple lines are split per line, because the lines of a
+    /// block are not necessarily contiguous in the outer document (comment prefixes).
+    pub fn remap_occurrences(&self, inner: Document) -> Vec<Occurrence> {
+        let mut occurrences = vec![];
+        for occ in inner.occurrences {
+            let range = match PackedRange::from_vec(&occ.range) {
+                Some(range) => range,
+                None => continue,
+            };
+
+            for inner_line in range.start_line..=range.end_line {
+                let block_line = match self.lines.get(inner_line as usize) {
+                    Some(block_line) => block_line,
+                    None => continue,
+                };
+
+                let start = if inner_line == range.start_line {
+                    range.start_col
+                } else {
+                    0
+                };
+                let end = if inner_line == range.end_line {
+                    range.end_col
+                } else {
+                    block_line.text.chars().count() as i32
+                };
+                if start >= end {
+                    continue;
+                }
+
+                let line = block_line.line as i32;
+                let column = block_line.column as i32;
+                occurrences.push(Occurrence {
+                    range: vec![line, column + start, column + end],
+                    ..occ.clone()
+                });
+            }
+        }
+
+        occurrences
+    }
+}
+
+/// Finds the fenced code blocks of a Markdown document.
+pub(crate) fn markdown_blocks(code: &str) -> Vec<FencedBlock> {
+    let lines = code.lines().enumerate().map(|(line, text)| BlockLine {
+        line,
+        column: 0,
+        text: text.to_string(),
+    });
+
+    fenced_blocks(lines)
+}
+
+/// Finds the fenced code blocks inside of runs of comment lines that start with one of
+/// `prefixes`. `is_comment` is called with the (line, column) of every prefix, so that
+/// lines that only look like comments (e.g. `*ptr = 0;`), or comments of the wrong kind,
+/// can be skipped.
+pub(crate) fn comment_blocks(
+    code: &str,
+    prefixes: &[&str],
+    is_comment: impl Fn(usize, usize) -> bool,
+) -> Vec<FencedBlock> {
+    let mut blocks = vec![];
+
+    // Consecutive comment lines with the same prefix
+    let mut run: Vec<BlockLine> = vec![];
+    let mut run_prefix = "";
+
+    for (line, text) in code.lines().enumerate() {
+        let trimmed = text.trim_start();
+        let indent = text.chars().count() - trimmed.chars().count();
+
+        let comment = prefixes
+            .iter()
+            .find(|prefix| trimmed.starts_with(*prefix))
+            .filter(|_| is_comment(line, indent))
+            .map(|prefix| {
+                let rest = &trimmed[prefix.len()..];
+                // A single space after the prefix is part of the comment syntax
+                let rest_without_space = rest.strip_prefix(' ').unwrap_or(rest);
+                let column =
+                    indent + prefix.chars().count() + rest.len() - rest_without_space.len();
+                (
+                    *prefix,
+                    BlockLine {
+                        line,
+                        column,
+                        text: rest_without_space.to_string(),
+                    },
+                )
+            });
+
+        match comment {
+            Some((prefix, block_line)) if prefix == run_prefix => run.push(block_line),
+            Some((prefix, block_line)) => {
+                blocks.extend(fenced_blocks(run.drain(..)));
+                run_prefix = prefix;
+                run.push(block_line);
+            }
+            None => {
+                blocks.extend(fenced_blocks(run.drain(..)));
+                run_prefix = "";
+            }
+        }
+    }
+
+    blocks.extend(fenced_blocks(run.drain(..)));
+    blocks
+}
+
+fn opening_fence(text: &str) -> Option<(char, usize, usize, &str)> {
+    let trimmed = text.trim_start_matches(' ');
+    let indent = text.len() - trimmed.len();
+    if indent > 3 {
+        return None;
+    }
+
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence_len = trimmed.chars().take_while(|c| *c == fence_char).count();
+    if fence_len < 3 {
+        return None;
+    }
+
+    let info = trimmed[fence_len..].trim();
+    // Backtick fences can't have backticks in their info string
+    if fence_char == '`' && info.contains('`') {
+        return None;
+    }
+
+    Some((fence_char, fence_len, indent, info))
+}
+
+fn is_closing_fence(text: &str, fence_char: char, fence_len: usize) -> bool {
+    let trimmed = text.trim_start_matches(' ');
+    if text.len() - trimmed.len() > 3 {
+        return false;
+    }
+
+    let len = trimmed.chars().take_while(|c| *c == fence_char).count();
+    len >= fence_len && trimmed[len..].trim().is_empty()
+}
+
+// CommonMark fenced code blocks. Unclosed blocks run until the end of the lines.
+fn fenced_blocks(lines: impl IntoIterator<Item = BlockLine>) -> Vec<FencedBlock> {
+    let mut blocks = vec![];
+    let mut open: Option<(char, usize, usize, FencedBlock)> = None;
+
+    for line in lines {
+        match &mut open {
+            None => {
+                if let Some((fence_char, fence_len, indent, info)) = opening_fence(&line.text) {
+                    open = Some((
+                        fence_char,
+                        fence_len,
+                        indent,
+                        FencedBlock {
+                            info: info.to_string(),
+                            lines: vec![],
+                        },
+                    ));
+                }
+            }
+            Some((fence_char, fence_len, indent, block)) => {
+                if is_closing_fence(&line.text, *fence_char, *fence_len) {
+                    blocks.extend(open.take().map(|(_, _, _, block)| block));
+                    continue;
+                }
+
+                // Content lines lose as much indentation as the opening fence had
+                let stripped = line
+                    .text
+                    .chars()
+                    .take(*indent)
+                    .take_while(|c| *c == ' ')
+                    .count();
+                block.lines.push(BlockLine {
+                    line: line.line,
+                    column: line.column + stripped,
+                    text: line.text[stripped..].to_string(),
+                });
+            }
+        }
+    }
+
+    blocks.extend(open.map(|(_, _, _, block)| block));
+    blocks.retain(|block| !block.lines.is_empty());
+    blocks
+}
+
+/// Removes the `cuts` from the ranges of the `base` occurrences, splitting them where
+/// needed, and adds the `additions`. The base occurrences don't overlap each other, so
+/// as long as the additions are covered by the cuts the result doesn't overlap either.
+pub(crate) fn splice_occurrences(
+    base: Vec<Occurrence>,
+    mut cuts: Vec<PackedRange>,
+    additions: Vec<Occurrence>,
+) -> Vec<Occurrence> {
+    cuts.sort_by_key(|range| (start_of(range), end_of(range)));
+
+    let mut result = Vec::with_capacity(base.len() + additions.len());
+    for occ in base {
+        let range = match PackedRange::from_vec(&occ.range) {
+            Some(range) => range,
+            None => {
+                result.push(occ);
+                continue;
+            }
+        };
+
+        let first_cut = cuts.partition_point(|cut| end_of(cut) <= start_of(&range));
+        let overlapping = cuts[first_cut..]
+            .iter()
+            .take_while(|cut| start_of(cut) < end_of(&range));
+
+        let mut cursor = start_of(&range);
+        for cut in overlapping {
+            if start_of(cut) > cursor {
+                result.push(Occurrence {
+                    range: range_between(cursor, start_of(cut)).to_vec(),
+                    ..occ.clone()
+                });
+            }
+            cursor = cursor.max(end_of(cut));
+        }
+
+        if cursor < end_of(&range) {
+            result.push(Occurrence {
+                range: range_between(cursor, end_of(&range)).to_vec(),
+                ..occ
+            });
+        }
+    }
+
+    result.extend(additions);
+    result.sort_by_key(|occ| {
+        let range = PackedRange::from_vec(&occ.range).unwrap_or_default();
+        start_of(&range)
+    });
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use scip::types::SyntaxKind;
+
+    use super::*;
+
+    fn occurrence(range: Vec<i32>, kind: SyntaxKind) -> Occurrence {
+        Occurrence {
+            range,
+            syntax_kind: kind.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_markdown_blocks() {
+        let code = "# Title\n\n```go\npackage main\n```\n\n  ~~~~ python extra\n  x = 1\n   y\n  ~~~~\n\n```\nunterminated";
+        let blocks = markdown_blocks(code);
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].language(None).as_deref(), Some("go"));
+        assert_eq!(blocks[0].code(), "package main\n");
+
+        assert_eq!(blocks[1].language(None).as_deref(), Some("python"));
+        assert_eq!(blocks[1].code(), "x = 1\n y\n");
+        assert_eq!(blocks[1].lines[0].column, 2);
+
+        assert_eq!(blocks[2].language(None), None);
+        assert_eq!(blocks[2].code(), "unterminated\n");
+    }
+
+    #[test]
+    fn test_comment_blocks() {
+        let code = "/// Adds one.\n///\n/// ```\n/// assert_eq!(add_one(1), 2);\n/// ```\nfn add_one(x: i32) -> i32 { x + 1 }\n";
+        let blocks = comment_blocks(code, &["///", "//"], |_, _| true);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].language(Some("rust")).as_deref(), Some("rust"));
+        assert_eq!(
+            blocks[0].lines,
+            vec![BlockLine {
+                line: 3,
+                column: 4,
+                text: "assert_eq!(add_one(1), 2);".to_string(),
+            }]
+        );
+
+        // Lines that aren't comments break up blocks
+        assert!(comment_blocks(code, &["///"], |line, _| line != 3).is_empty());
+    }
+
+    #[test]
+    fn test_rustdoc_attributes() {
+        let block = FencedBlock {
+            info: "no_run".to_string(),
+            lines: vec![],
+        };
+        assert_eq!(block.language(Some("rust")).as_deref(), Some("rust"));
+        assert_eq!(block.language(None).as_deref(), Some("no_run"));
+        assert_eq!(block.parser(Some("rust")), Some(BundledParser::Rust));
+    }
+
+    #[test]
+    fn test_fence_parsers() {
+        let parser = |info: &str| {
+            FencedBlock {
+                info: info.to_string(),
+                lines: vec![],
+            }
+            .parser(None)
+        };
+
+        assert_eq!(parser("golang"), Some(BundledParser::Go));
+        assert_eq!(parser("rs"), Some(BundledParser::Rust));
+        assert_eq!(parser("C++"), Some(BundledParser::Cpp));
+        assert_eq!(parser("py {linenos=true}"), Some(BundledParser::Python));
+        assert_eq!(parser("markdown"), None);
+        assert_eq!(parser(""), None);
+    }
+
+    #[test]
+    fn test_remap_occurrences() {
+        let block = FencedBlock {
+            info: "go".to_string(),
+            lines: vec![
+                BlockLine {
+                    line: 10,
+                    column: 3,
+                    text: "x := `a".to_string(),
+                },
+                BlockLine {
+                    line: 11,
+                    column: 3,
+                    text: "b`".to_string(),
+                },
+            ],
+        };
+        let inner = Document {
+            occurrences: vec![
+                occurrence(vec![0, 0, 1], SyntaxKind::Identifier),
+                occurrence(vec![0, 5, 1, 2], SyntaxKind::StringLiteral),
+            ],
+            ..Default::default()
+        };
+
+        let ranges: Vec<_> = block
+            .remap_occurrences(inner)
+            .into_iter()
+            .map(|occ| occ.range)
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![vec![10, 3, 4], vec![10, 8, 10], vec![11, 3, 5]]
+        );
+    }
+
+    #[test]
+    fn test_splice_occurrences() {
+        let base = vec![
+            occurrence(vec![0, 0, 20], SyntaxKind::Comment),
+            occurrence(vec![1, 0, 3, 5], SyntaxKind::StringLiteral),
+        ];
+        let additions = vec![occurrence(vec![0, 8, 12], SyntaxKind::Keyword)];
+        let cuts = vec![
+            PackedRange::from_vec(&[0, 8, 12]).unwrap(),
+            PackedRange::from_vec(&[2, 0, 2, 10]).unwrap(),
+        ];
+
+        let ranges: Vec<_> = splice_occurrences(base, cuts, additions)
+            .into_iter()
+            .map(|occ| (occ.range, occ.syntax_kind.enum_value_or_default()))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                (vec![0, 0, 8], SyntaxKind::Comment),
+                (vec![0, 8, 12], SyntaxKind::Keyword),
+                (vec![0, 12, 20], SyntaxKind::Comment),
+                (vec![1, 0, 2, 0], SyntaxKind::StringLiteral),
+                (vec![2, 10, 3, 5], SyntaxKind::StringLiteral),
+            ]
+        );
+    }
+}
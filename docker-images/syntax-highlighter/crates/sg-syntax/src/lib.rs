@@ -2,7 +2,10 @@ use std::path::Path;
 
 use protobuf::Message;
 use rocket::serde::json::{json, Value as JsonValue};
-use serde::Deserialize;
+use scip::types::{Document, Occurrence};
+use scip_syntax::encoding::{self, DecodedText};
+use scip_treesitter_languages::highlights::get_highlighting_configuration;
+use serde::{Deserialize, Serialize};
 use sg_treesitter::jsonify_err;
 use syntect::{
     html::ClassStyle,
@@ -11,8 +14,10 @@ use syntect::{
 
 mod sg_treesitter;
 pub use sg_treesitter::{
-    index_language as treesitter_index, index_language_with_config as treesitter_index_with_config,
-    lsif_highlight,
+    error_ratio as treesitter_error_ratio, index_language as treesitter_index,
+    index_language_with_config as treesitter_index_with_config,
+    index_language_with_tree as treesitter_index_with_tree, lsif_highlight,
+    parse_error_ratio as treesitter_parse_error_ratio, parse_tree as treesitter_parse_tree,
 };
 
 mod sg_syntect;
@@ -22,11 +27,21 @@ use tree_sitter_highlight::Error;
 use crate::sg_treesitter::treesitter_language;
 
 mod sg_sciptect;
+pub use sg_sciptect::DocumentGenerator;
 
-thread_local! {
-    pub(crate) static SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
-}
+mod fenced_code;
+
+pub mod notebook;
+use notebook::{CellType, Notebook, NotebookLayout};
+
+pub mod classify;
+use classify::{classify, skip_policy};
 
+pub mod scope_mapping;
+pub mod semantic_tokens;
+pub mod syntaxes;
+use syntaxes::syntax_set;
+pub mod textmate;
 
 /// Struct from: internal/gosyntect/gosyntect.go
 ///
@@ -42,8 +57,18 @@ pub struct SourcegraphQuery {
     pub extension: String,
 
     // Contents of the file
+    #[serde(default)]
     pub code: String,
 
+    // Contents of the file as base64 encoded bytes, for files that aren't UTF-8.
+    // Replaces `code` when set, see the encoding module of scip-syntax.
+    #[serde(default)]
+    pub code_base64: Option<String>,
+
+    // Encoding of `code_base64`, e.g. "Shift_JIS". Detected when not set.
+    #[serde(default)]
+    pub charset: Option<String>,
+
     // default empty string value for backwards compat with clients who do not specify this field.
     #[serde(default)]
     pub filepath: String,
@@ -57,7 +82,7 @@ pub struct SourcegraphQuery {
 }
 
 // NOTE: Keep in sync: internal/gosyntect/gosyntect.go
-#[derive(Deserialize, Default, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SyntaxEngine {
     #[default]
     #[serde(rename = "syntect")]
@@ -68,6 +93,27 @@ pub enum SyntaxEngine {
 
     #[serde(rename = "scip-syntax")]
     ScipSyntax,
+
+    // Uses scip-syntax when tree-sitter can parse the file well enough,
+    // and falls back to syntect otherwise. See AUTO_ENGINE_MAX_ERROR_RATIO.
+    #[serde(rename = "auto")]
+    Auto,
+}
+
+/// The largest fraction of a tree-sitter parse tree that may be made up of `ERROR`
+/// and `MISSING` nodes before the auto engine falls back to syntect.
+pub const AUTO_ENGINE_MAX_ERROR_RATIO: f64 = 0.1;
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScipResponseFormat {
+    // Base64 encoded SCIP document
+    #[default]
+    #[serde(rename = "scip")]
+    Scip,
+
+    // LSP semantic tokens, see the semantic_tokens module
+    #[serde(rename = "semantic-tokens")]
+    SemanticTokens,
 }
 
 #[derive(Deserialize, Default, Debug)]
@@ -76,8 +122,22 @@ pub struct ScipHighlightQuery {
     pub engine: SyntaxEngine,
 
     // Contents of the file
+    #[serde(default)]
     pub code: String,
 
+    // Same as for SourcegraphQuery
+    #[serde(default)]
+    pub code_base64: Option<String>,
+
+    #[serde(default)]
+    pub charset: Option<String>,
+
+    // Return ranges with byte columns in the encoding of `code_base64`, instead of
+    // character columns. Only applies to the SCIP format, semantic tokens always use
+    // the UTF-16 columns of the decoded code.
+    #[serde(default)]
+    pub original_positions: bool,
+
     // filepath is only used if language is None.
     pub filepath: String,
 
@@ -88,13 +148,23 @@ pub struct ScipHighlightQuery {
     // line_length_limit is used to limit syntect problems when
     // parsing very long lines
     pub line_length_limit: Option<usize>,
+
+    // Attach tree-sitter syntax errors as diagnostics to the returned document.
+    // Ignored when the document is produced by syntect.
+    #[serde(default)]
+    pub include_diagnostics: bool,
+
+    // How the highlighted document is returned. Diagnostics are only part of the
+    // SCIP format.
+    #[serde(default)]
+    pub format: ScipResponseFormat,
 }
 
 pub fn determine_filetype(q: &SourcegraphQuery) -> String {
-    let filetype = SYNTAX_SET.with(|syntax_set| match determine_language(q, syntax_set) {
+    let filetype = match determine_language(q, syntax_set()) {
         Ok(language) => language.name.clone(),
         Err(_) => "".to_owned(),
-    });
+    };
 
     if filetype.is_empty() || filetype.to_lowercase() == "plain text" {
         #[allow(clippy::single_match)]
@@ -212,82 +282,346 @@ pub fn determine_language<'a>(
 
 pub fn list_features() {
     // List supported file extensions.
-    SYNTAX_SET.with(|syntax_set| {
-        println!("## Supported file extensions:");
-        println!();
-        for sd in syntax_set.syntaxes() {
-            println!("- {} (`{}`)", sd.name, sd.file_extensions.join("`, `"));
-        }
-        println!();
-    });
+    println!("## Supported file extensions:");
+    println!();
+    for sd in syntax_set().syntaxes() {
+        println!("- {} (`{}`)", sd.name, sd.file_extensions.join("`, `"));
+    }
+    println!();
 }
 
-pub fn syntect_highlight(q: SourcegraphQuery) -> JsonValue {
-    SYNTAX_SET.with(|syntax_set| {
-        // Determine syntax definition by extension.
-        let syntax_def = match determine_language(&q, syntax_set) {
+pub fn syntect_highlight(mut q: SourcegraphQuery) -> JsonValue {
+    let input = match decode_input(&mut q.code, &q.code_base64, &q.charset) {
+        Ok(input) => input,
+        Err(err) => return err,
+    };
+
+    let syntax_set = syntax_set();
+    let flags = classify(&q.filepath, &q.code);
+
+    // Determine syntax definition by extension. Skipped files are still rendered as a
+    // table, so that clients don't need a separate code path for them.
+    let syntax_def = if skip_policy().should_skip(&flags) {
+        syntax_set.find_syntax_plain_text()
+    } else {
+        match determine_language(&q, syntax_set) {
             Ok(v) => v,
             Err(e) => return e,
-        };
+        }
+    };
+
+    let output = ClassedTableGenerator::new(
+        syntax_set,
+        syntax_def,
+        &q.code,
+        q.line_length_limit,
+        ClassStyle::SpacedPrefixed { prefix: "hl-" },
+    )
+    .generate();
+
+    let mut response =
+        json!({ "data": output, "plaintext": syntax_def.name == "Plain Text", "flags": flags });
+    if let Some(input) = &input {
+        response["encoding"] = input.to_json();
+    }
+    response
+}
+
+pub fn scip_highlight(mut q: ScipHighlightQuery) -> Result<JsonValue, JsonValue> {
+    let input = decode_input(&mut q.code, &q.code_base64, &q.charset)?;
+    let flags = classify(&q.filepath, &q.code);
+
+    let mut response = if skip_policy().should_skip(&flags) {
+        let mut response = scip_response(&q, q.engine, &Document::new(), &q.code)?;
+        response["plaintext"] = json!(true);
+        response
+    } else if notebook::is_notebook(&q.filepath) {
+        let (engine, document, layout) = notebook_document(&q)?;
+        let mut response = scip_response(&q, engine, &document, &layout.code)?;
+        response["notebook"] = json!(layout);
+        response
+    } else {
+        let (engine, mut document) = highlight_document(&q)?;
+        let original_positions = q.original_positions && q.format == ScipResponseFormat::Scip;
+        if let Some(input) = input.as_ref().filter(|_| original_positions) {
+            let positions = input.decoded.position_map(&input.bytes);
+            for occ in &mut document.occurrences {
+                occ.range = positions.original_range(&occ.range);
+            }
+        }
 
-        let output = ClassedTableGenerator::new(
-            syntax_set,
-            syntax_def,
-            &q.code,
-            q.line_length_limit,
-            ClassStyle::SpacedPrefixed { prefix: "hl-" },
-        )
-        .generate();
-
-        json!({ "data": output, "plaintext": syntax_def.name == "Plain Text", })
-    })
+        scip_response(&q, engine, &document, &q.code)?
+    };
+
+    response["flags"] = json!(flags);
+    if let Some(input) = &input {
+        response["encoding"] = input.to_json();
+    }
+    Ok(response)
 }
 
-pub fn scip_highlight(q: ScipHighlightQuery) -> Result<JsonValue, JsonValue> {
-    match q.engine {
-        SyntaxEngine::Syntect => SYNTAX_SET.with(|ss| {
-            let sg_query = SourcegraphQuery {
-                extension: "".to_string(),
-                filepath: q.filepath.clone(),
-                filetype: q.filetype.clone(),
-                line_length_limit: None,
-                code: q.code.clone(),
-            };
-
-            let language = determine_language(&sg_query, ss).map_err(jsonify_err)?;
-            let document = sg_sciptect::DocumentGenerator::new(
-                ss,
-                language,
-                q.code.as_str(),
-                q.line_length_limit,
-            )
-            .generate();
-            let encoded = document.write_to_bytes().map_err(jsonify_err)?;
-            Ok(json!({"scip": base64::encode(encoded), "plaintext": false}))
-        }),
+/// Code that was sent as bytes in `code_base64`.
+pub(crate) struct DecodedInput {
+    pub decoded: DecodedText,
+    pub bytes: Vec<u8>,
+}
+
+impl DecodedInput {
+    pub fn to_json(&self) -> JsonValue {
+        json!({
+            "name": self.decoded.encoding_name(),
+            "source": self.decoded.source,
+            "had_errors": self.decoded.had_errors,
+        })
+    }
+}
+
+/// Replaces `code` with the UTF-8 version of `code_base64`, if the query has one.
+pub(crate) fn decode_input(
+    code: &mut String,
+    code_base64: &Option<String>,
+    charset: &Option<String>,
+) -> Result<Option<DecodedInput>, JsonValue> {
+    let encoded = match code_base64 {
+        Some(encoded) => encoded,
+        None => return Ok(None),
+    };
+
+    let bytes = base64::decode(encoded).map_err(jsonify_err)?;
+    let mut decoded = encoding::decode(&bytes, charset.as_deref()).map_err(jsonify_err)?;
+    *code = std::mem::take(&mut decoded.text);
+
+    Ok(Some(DecodedInput { decoded, bytes }))
+}
+
+fn highlight_document(q: &ScipHighlightQuery) -> Result<(SyntaxEngine, Document), JsonValue> {
+    let (engine, document) = match q.engine {
+        SyntaxEngine::Syntect => (SyntaxEngine::Syntect, syntect_document(q)?),
         SyntaxEngine::TreeSitter | SyntaxEngine::ScipSyntax => {
             let language = q
                 .filetype
+                .as_ref()
                 .ok_or_else(|| json!({"error": "Must pass a language for /scip" }))?
                 .to_lowercase();
 
             let include_locals = q.engine == SyntaxEngine::ScipSyntax;
+            let document = treesitter_document(
+                treesitter_language(&language),
+                &q.code,
+                include_locals,
+                q.include_diagnostics,
+            )?;
+
+            (q.engine, document)
+        }
+        SyntaxEngine::Auto => auto_document(q)?,
+    };
+
+    Ok((engine, document))
+}
 
-            match treesitter_index(treesitter_language(&language), &q.code, include_locals) {
-                Ok(document) => {
-                    let encoded = document.write_to_bytes().map_err(jsonify_err)?;
+fn scip_response(
+    q: &ScipHighlightQuery,
+    engine: SyntaxEngine,
+    document: &Document,
+    code: &str,
+) -> Result<JsonValue, JsonValue> {
+    match q.format {
+        ScipResponseFormat::Scip => {
+            let encoded = document.write_to_bytes().map_err(jsonify_err)?;
+            Ok(json!({"scip": base64::encode(encoded), "plaintext": false, "engine": engine}))
+        }
+        ScipResponseFormat::SemanticTokens => Ok(json!({
+            "semantic_tokens": { "data": semantic_tokens::encode(document, code) },
+            "plaintext": false,
+            "engine": engine,
+        })),
+    }
+}
 
-                    Ok(json!({"scip": base64::encode(encoded), "plaintext": false}))
+// Highlights every cell of a notebook on its own. Markdown and raw cells always use
+// syntect, code cells use the requested engine if it supports the notebook language
+// and syntect otherwise. The reported engine is `auto` if the cells used different ones.
+fn notebook_document(
+    q: &ScipHighlightQuery,
+) -> Result<(SyntaxEngine, Document, NotebookLayout), JsonValue> {
+    let notebook = Notebook::parse(&q.code).map_err(jsonify_err)?;
+    let layout = notebook.layout();
+
+    let ss = syntax_set();
+    let mut document = Document::new();
+    let mut engines = vec![];
+
+    for (cell, cell_layout) in notebook.cells.iter().zip(&layout.cells) {
+        let code = cell.source.replace("\r\n", "\n");
+        let language = treesitter_language(&cell_layout.language);
+
+        let treesitter = match q.engine {
+            _ if cell.cell_type != CellType::Code => None,
+            SyntaxEngine::Syntect => None,
+            SyntaxEngine::TreeSitter | SyntaxEngine::ScipSyntax => {
+                if get_highlighting_configuration(language).is_some() {
+                    let include_locals = q.engine == SyntaxEngine::ScipSyntax;
+                    let cell_document = treesitter_document(
+                        language,
+                        &code,
+                        include_locals,
+                        q.include_diagnostics,
+                    )?;
+                    Some((q.engine, cell_document))
+                } else {
+                    None
                 }
-                Err(Error::InvalidLanguage) => Err(json!({
-                    "error": format!("{} is not a valid filetype for treesitter", language)
-                })),
-                Err(err) => Err(jsonify_err(err)),
             }
+            SyntaxEngine::Auto => auto_treesitter_document(language, &code, q.include_diagnostics)?
+                .map(|cell_document| (SyntaxEngine::ScipSyntax, cell_document)),
+        };
+
+        let (engine, mut cell_document) = match treesitter {
+            Some(treesitter) => treesitter,
+            None => {
+                let syntax = ss
+                    .syntaxes()
+                    .iter()
+                    .rev()
+                    .find(|s| s.name.to_lowercase() == cell_layout.language)
+                    .or_else(|| ss.find_syntax_by_token(&cell_layout.language))
+                    .unwrap_or_else(|| ss.find_syntax_plain_text());
+                let cell_document = syntect_generate(syntax, &code, q.line_length_limit);
+                (SyntaxEngine::Syntect, cell_document)
+            }
+        };
+
+        notebook::shift_occurrences(&mut cell_document.occurrences, cell_layout.start_line);
+        document.occurrences.extend(cell_document.occurrences);
+        engines.push(engine);
+    }
+
+    let engine = match engines.split_first() {
+        Some((first, rest)) if rest.iter().all(|engine| engine == first) => *first,
+        Some(_) => SyntaxEngine::Auto,
+        None => q.engine,
+    };
+
+    Ok((engine, document, layout))
+}
+
+fn syntect_document(q: &ScipHighlightQuery) -> Result<Document, JsonValue> {
+    let ss = syntax_set();
+    let sg_query = SourcegraphQuery {
+        extension: "".to_string(),
+        filepath: q.filepath.clone(),
+        filetype: q.filetype.clone(),
+        line_length_limit: None,
+        code: q.code.clone(),
+        ..Default::default()
+    };
+
+    let language = determine_language(&sg_query, ss).map_err(jsonify_err)?;
+    Ok(syntect_generate(language, &q.code, q.line_length_limit))
+}
+
+fn syntect_generate(
+    language: &SyntaxReference,
+    code: &str,
+    line_length_limit: Option<usize>,
+) -> Document {
+    let ss = syntax_set();
+    let mut document =
+        sg_sciptect::DocumentGenerator::new(ss, language, code, line_length_limit).generate();
+
+    if language.name.to_lowercase().contains("markdown") {
+        document.occurrences = highlight_markdown_blocks(
+            code,
+            std::mem::take(&mut document.occurrences),
+            line_length_limit,
+        );
+    }
+
+    document
+}
+
+// Replaces the occurrences inside of fenced code blocks with the highlighting of the
+// language named by the fence. Blocks in languages we can't highlight are left alone.
+fn highlight_markdown_blocks(
+    code: &str,
+    occurrences: Vec<Occurrence>,
+    line_length_limit: Option<usize>,
+) -> Vec<Occurrence> {
+    let ss = syntax_set();
+
+    let mut cuts = vec![];
+    let mut additions = vec![];
+    for block in fenced_code::markdown_blocks(code) {
+        // tree-sitter is preferred, as it is for the auto engine
+        let inner = sg_treesitter::highlight_fenced_block(&block, None).or_else(|| {
+            let language = block.language(None)?;
+            let syntax = ss.find_syntax_by_token(&language)?;
+            Some(DocumentGenerator::new(ss, syntax, &block.code(), line_length_limit).generate())
+        });
+
+        if let (Some(inner), Some(range)) = (inner, block.content_range()) {
+            cuts.push(range);
+            additions.extend(block.remap_occurrences(inner));
+        }
+    }
+
+    fenced_code::splice_occurrences(occurrences, cuts, additions)
+}
+
+fn treesitter_document(
+    language: &str,
+    code: &str,
+    include_locals: bool,
+    include_diagnostics: bool,
+) -> Result<Document, JsonValue> {
+    treesitter_index(language, code, include_locals, include_diagnostics)
+        .map_err(|err| treesitter_error(language, err))
+}
+
+// Highlights `code` with tree-sitter, including locals, if it parses with few enough
+// errors for the auto engine. The tree is parsed once and reused for locals and diagnostics.
+fn auto_treesitter_document(
+    language: &str,
+    code: &str,
+    include_diagnostics: bool,
+) -> Result<Option<Document>, JsonValue> {
+    let tree = match treesitter_parse_tree(language, code) {
+        Some(tree) if treesitter_error_ratio(&tree) <= AUTO_ENGINE_MAX_ERROR_RATIO => tree,
+        _ => return Ok(None),
+    };
+
+    treesitter_index_with_tree(language, code, &tree, true, include_diagnostics)
+        .map(Some)
+        .map_err(|err| treesitter_error(language, err))
+}
+
+fn treesitter_error(language: &str, err: Error) -> JsonValue {
+    match err {
+        Error::InvalidLanguage => {
+            json!({ "error": format!("{} is not a valid filetype for treesitter", language) })
         }
+        err => jsonify_err(err),
     }
 }
 
+// Use tree-sitter when we have a parser for the language and the parse is mostly
+// free of errors. Otherwise tree-sitter would return garbage highlights (for example
+// for unsupported language extensions), so we let syntect have a go instead.
+fn auto_document(q: &ScipHighlightQuery) -> Result<(SyntaxEngine, Document), JsonValue> {
+    if let Some(filetype) = &q.filetype {
+        let filetype = filetype.to_lowercase();
+        let language = treesitter_language(&filetype);
+
+        if let Some(document) = auto_treesitter_document(language, &q.code, q.include_diagnostics)?
+        {
+            return Ok((SyntaxEngine::ScipSyntax, document));
+        }
+    }
+
+    Ok((SyntaxEngine::Syntect, syntect_document(q)?))
+}
+
 #[cfg(test)]
 mod tests {
     use syntect::parsing::SyntaxSet;
@@ -303,6 +637,7 @@ mod tests {
             code: "%".to_string(),
             line_length_limit: None,
             extension: String::new(),
+            ..Default::default()
         };
         let result = determine_language(&query, &syntax_set);
         assert_eq!(result.unwrap().name, "TeX");
@@ -317,8 +652,209 @@ mod tests {
             code: "/**".to_string(),
             line_length_limit: None,
             extension: String::new(),
+            ..Default::default()
         };
         let result = determine_language(&query, &syntax_set);
         assert_eq!(result.unwrap().name, "Apex");
     }
+
+    #[test]
+    fn auto_engine_uses_treesitter_for_valid_code() {
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::Auto,
+            code: "package main\n\nfunc main() {}\n".to_string(),
+            filepath: "main.go".to_string(),
+            filetype: Some("go".to_string()),
+            line_length_limit: None,
+            include_diagnostics: false,
+            format: ScipResponseFormat::Scip,
+            ..Default::default()
+        };
+        let result = scip_highlight(query).unwrap();
+        assert_eq!(result["engine"], "scip-syntax");
+    }
+
+    #[test]
+    fn auto_engine_falls_back_to_syntect() {
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::Auto,
+            code: "<html><body><p>This is not Go at all</p></body></html>\n".to_string(),
+            filepath: "main.go".to_string(),
+            filetype: Some("go".to_string()),
+            line_length_limit: None,
+            include_diagnostics: false,
+            format: ScipResponseFormat::Scip,
+            ..Default::default()
+        };
+        let result = scip_highlight(query).unwrap();
+        assert_eq!(result["engine"], "syntect");
+    }
+
+    #[test]
+    fn scip_highlight_as_semantic_tokens() {
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::TreeSitter,
+            code: "package main\n".to_string(),
+            filepath: "main.go".to_string(),
+            filetype: Some("go".to_string()),
+            line_length_limit: None,
+            include_diagnostics: false,
+            format: ScipResponseFormat::SemanticTokens,
+            ..Default::default()
+        };
+        let result = scip_highlight(query).unwrap();
+        assert!(result.get("scip").is_none());
+
+        let data = result["semantic_tokens"]["data"].as_array().unwrap();
+        assert_eq!(data.len() % 5, 0);
+        // `package` is the first token
+        assert_eq!(data[..3], [json!(0), json!(0), json!(7)]);
+    }
+
+    #[test]
+    fn notebook_cells_are_highlighted_separately() {
+        let notebook = r##"{
+          "metadata": { "language_info": { "name": "python" } },
+          "cells": [
+            { "cell_type": "markdown", "source": ["# Title\n"] },
+            { "cell_type": "code", "source": ["def f():\n", "    return 1\n"] }
+          ]
+        }"##;
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::TreeSitter,
+            code: notebook.to_string(),
+            filepath: "analysis.ipynb".to_string(),
+            filetype: None,
+            line_length_limit: None,
+            include_diagnostics: false,
+            format: ScipResponseFormat::Scip,
+            ..Default::default()
+        };
+        let result = scip_highlight(query).unwrap();
+
+        // The markdown cell can only be highlighted by syntect
+        assert_eq!(result["engine"], "auto");
+        assert_eq!(result["notebook"]["cells"][1]["start_line"], 1);
+        assert_eq!(
+            result["notebook"]["code"],
+            "# Title\ndef f():\n    return 1\n"
+        );
+
+        let encoded = base64::decode(result["scip"].as_str().unwrap()).unwrap();
+        let document = Document::parse_from_bytes(&encoded).unwrap();
+        assert!(document
+            .occurrences
+            .iter()
+            .any(|occ| occ.range == vec![1, 0, 3]
+                && occ.syntax_kind.enum_value_or_default() == scip::types::SyntaxKind::Keyword));
+    }
+
+    #[test]
+    fn shift_jis_code_with_original_positions() {
+        let (bytes, _, _) =
+            encoding_rs::SHIFT_JIS.encode("package main\n\n// こんにちは\nvar x = \"世界\"\n");
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::TreeSitter,
+            code_base64: Some(base64::encode(bytes)),
+            charset: Some("shift_jis".to_string()),
+            original_positions: true,
+            filepath: "main.go".to_string(),
+            filetype: Some("go".to_string()),
+            ..Default::default()
+        };
+        let result = scip_highlight(query).unwrap();
+        assert_eq!(result["encoding"]["name"], "Shift_JIS");
+        assert_eq!(result["encoding"]["source"], "charset");
+
+        let encoded = base64::decode(result["scip"].as_str().unwrap()).unwrap();
+        let document = Document::parse_from_bytes(&encoded).unwrap();
+        // The string is 4 characters, but 6 bytes in Shift_JIS
+        assert!(document
+            .occurrences
+            .iter()
+            .any(|occ| occ.range == vec![3, 8, 14]));
+    }
+
+    #[test]
+    fn shift_jis_code_as_semantic_tokens_ignores_original_positions() {
+        let (bytes, _, _) =
+            encoding_rs::SHIFT_JIS.encode("package main\n\n// こんにちは\nvar x = \"世界\" + y\n");
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::TreeSitter,
+            code_base64: Some(base64::encode(bytes)),
+            charset: Some("shift_jis".to_string()),
+            original_positions: true,
+            filepath: "main.go".to_string(),
+            filetype: Some("go".to_string()),
+            format: ScipResponseFormat::SemanticTokens,
+            ..Default::default()
+        };
+        let result = scip_highlight(query).unwrap();
+
+        // Undo the delta encoding
+        let data: Vec<u64> = result["semantic_tokens"]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n.as_u64().unwrap())
+            .collect();
+        let (mut line, mut start) = (0, 0);
+        let mut tokens = vec![];
+        for token in data.chunks(5) {
+            if token[0] > 0 {
+                start = 0;
+            }
+            line += token[0];
+            start += token[1];
+            tokens.push((line, start, token[2]));
+        }
+
+        // `y` is at character 15, it would be at byte 17 in Shift_JIS
+        assert!(tokens.contains(&(3, 15, 1)), "{tokens:?}");
+    }
+
+    #[test]
+    fn binary_files_are_flagged_but_still_highlighted_by_default() {
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::Syntect,
+            code: "\u{89}PNG\r\n\u{1a}\n\0\0\0\rIHDR".to_string(),
+            filepath: "logo.png".to_string(),
+            filetype: None,
+            line_length_limit: None,
+            include_diagnostics: false,
+            format: ScipResponseFormat::Scip,
+            ..Default::default()
+        };
+        let result = scip_highlight(query).unwrap();
+
+        // Only skipped when SKIP_HIGHLIGHTING contains `binary`
+        assert_eq!(result["plaintext"], false);
+        assert_eq!(result["flags"]["binary"], true);
+        assert_eq!(result["flags"]["minified"], false);
+    }
+
+    #[test]
+    fn markdown_fenced_code_blocks() {
+        let query = ScipHighlightQuery {
+            engine: SyntaxEngine::Syntect,
+            code: "# Example\n\n```go\nfunc main() {}\n```\n".to_string(),
+            filepath: "README.md".to_string(),
+            filetype: None,
+            line_length_limit: None,
+            include_diagnostics: false,
+            format: ScipResponseFormat::Scip,
+            ..Default::default()
+        };
+        let document = syntect_document(&query).unwrap();
+
+        let func = document
+            .occurrences
+            .iter()
+            .find(|occ| occ.range == vec![3, 0, 4])
+            .expect("occurrence for func");
+        assert_eq!(
+            func.syntax_kind.enum_value_or_default(),
+            scip::types::SyntaxKind::Keyword
+        );
+    }
 }
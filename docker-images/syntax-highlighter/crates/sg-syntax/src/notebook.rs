@@ -0,0 +1,289 @@
+//! Jupyter notebooks (`.ipynb`).
+//!
+//! A notebook is a JSON document, which is not useful to highlight as such. Instead its
+//! cells are laid out one after another in a virtual document, and every cell is
+//! highlighted in its own language. Occurrence ranges refer to the virtual document,
+//! which is returned together with the position of every cell so that clients can
+//! render it.
+
+use anyhow::{Context, Result};
+use scip::types::{Document, Occurrence};
+use scip_treesitter_languages::parsers::BundledParser;
+use serde::{Deserialize, Serialize};
+
+// Notebooks written by Jupyter don't always declare their language, and most of them
+// are Python.
+const DEFAULT_LANGUAGE: &str = "python";
+
+pub fn is_notebook(filepath: &str) -> bool {
+    filepath.to_lowercase().ends_with(".ipynb")
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellType {
+    #[serde(rename = "code")]
+    Code,
+
+    #[serde(rename = "markdown")]
+    Markdown,
+
+    // Raw cells are passed through as plain text
+    #[serde(rename = "raw")]
+    Raw,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cell {
+    pub cell_type: CellType,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notebook {
+    // Language of the code cells, taken from the kernel metadata
+    pub language: String,
+    pub cells: Vec<Cell>,
+}
+
+/// Where a cell ended up in the virtual document. Lines are 0-based.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct CellLayout {
+    pub cell_type: CellType,
+    pub language: String,
+    pub start_line: usize,
+    pub line_count: usize,
+}
+
+/// The virtual document that occurrence ranges of a notebook refer to.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct NotebookLayout {
+    pub code: String,
+    pub cells: Vec<CellLayout>,
+}
+
+// The parts of the nbformat 4 schema we care about:
+// https://github.com/jupyter/nbformat/blob/main/nbformat/v4/nbformat.v4.schema.json
+#[derive(Deserialize)]
+struct RawNotebook {
+    #[serde(default)]
+    cells: Vec<RawCell>,
+    #[serde(default)]
+    metadata: RawMetadata,
+}
+
+#[derive(Deserialize, Default)]
+struct RawMetadata {
+    language_info: Option<RawLanguageInfo>,
+    kernelspec: Option<RawKernelSpec>,
+}
+
+#[derive(Deserialize)]
+struct RawLanguageInfo {
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawKernelSpec {
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawCell {
+    cell_type: String,
+    #[serde(default)]
+    source: RawSource,
+}
+
+// Sources are usually stored as a list of lines (with their line endings), but a
+// single string is valid as well.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSource {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Default for RawSource {
+    fn default() -> Self {
+        RawSource::Text(String::new())
+    }
+}
+
+impl Notebook {
+    pub fn parse(json: &str) -> Result<Self> {
+        let raw: RawNotebook = serde_json::from_str(json).context("invalid notebook")?;
+
+        let language = raw
+            .metadata
+            .language_info
+            .and_then(|info| info.name)
+            .or_else(|| raw.metadata.kernelspec.and_then(|spec| spec.language))
+            .filter(|language| !language.is_empty())
+            .map_or_else(|| DEFAULT_LANGUAGE.to_string(), |l| l.to_lowercase());
+
+        let cells = raw
+            .cells
+            .into_iter()
+            .map(|cell| Cell {
+                cell_type: match cell.cell_type.as_str() {
+                    "code" => CellType::Code,
+                    "markdown" => CellType::Markdown,
+                    _ => CellType::Raw,
+                },
+                source: match cell.source {
+                    RawSource::Text(text) => text,
+                    RawSource::Lines(lines) => lines.concat(),
+                },
+            })
+            .collect();
+
+        Ok(Notebook { language, cells })
+    }
+
+    pub fn cell_language(&self, cell: &Cell) -> &str {
+        match cell.cell_type {
+            CellType::Code => &self.language,
+            CellType::Markdown => "markdown",
+            CellType::Raw => "plain text",
+        }
+    }
+
+    /// Lays out the cells one after another, every cell starting on a new line.
+    pub fn layout(&self) -> NotebookLayout {
+        let mut code = String::new();
+        let mut cells = vec![];
+        let mut start_line = 0;
+
+        for cell in &self.cells {
+            let source = cell.source.replace("\r\n", "\n");
+            let line_count = source.lines().count();
+
+            code += &source;
+            if !source.is_empty() && !source.ends_with('\n') {
+                code += "\n";
+            }
+
+            cells.push(CellLayout {
+                cell_type: cell.cell_type,
+                language: self.cell_language(cell).to_string(),
+                start_line,
+                line_count,
+            });
+            start_line += line_count;
+        }
+
+        NotebookLayout { code, cells }
+    }
+
+    /// Symbols defined in the code cells, with ranges in the virtual document.
+    pub fn symbols(&self, layout: &NotebookLayout) -> Result<Document> {
+        let mut document = Document::new();
+
+        let parser = match BundledParser::get_parser(&self.language) {
+            Some(parser) => parser,
+            None => return Ok(document),
+        };
+        if scip_syntax::languages::get_tag_configuration(parser).is_none() {
+            return Ok(document);
+        }
+
+        for (cell, cell_layout) in self.cells.iter().zip(&layout.cells) {
+            if cell.cell_type != CellType::Code {
+                continue;
+            }
+
+            let source = cell.source.replace("\r\n", "\n");
+            let mut cell_document = scip_syntax::get_symbols(parser, source.as_bytes())?;
+            shift_occurrences(&mut cell_document.occurrences, cell_layout.start_line);
+
+            document.occurrences.extend(cell_document.occurrences);
+            document.symbols.extend(cell_document.symbols);
+        }
+
+        Ok(document)
+    }
+}
+
+/// Moves occurrences of a single cell to the line the cell starts on.
+pub fn shift_occurrences(occurrences: &mut [Occurrence], start_line: usize) {
+    let shift = |range: &mut Vec<i32>| match range.len() {
+        3 => range[0] += start_line as i32,
+        4 => {
+            range[0] += start_line as i32;
+            range[2] += start_line as i32;
+        }
+        _ => {}
+    };
+
+    for occ in occurrences {
+        shift(&mut occ.range);
+        shift(&mut occ.enclosing_range);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const NOTEBOOK: &str = r##"{
+      "metadata": { "kernelspec": { "language": "python", "name": "python3" } },
+      "nbformat": 4,
+      "nbformat_minor": 5,
+      "cells": [
+        { "cell_type": "markdown", "metadata": {}, "source": ["# Title\n", "Some *text*"] },
+        { "cell_type": "code", "metadata": {}, "outputs": [], "source": "import os\n\ndef main():\n    pass" },
+        { "cell_type": "raw", "metadata": {}, "source": [] },
+        { "cell_type": "code", "metadata": {}, "outputs": [], "source": ["class Thing:\n", "    pass\n"] }
+      ]
+    }"##;
+
+    #[test]
+    fn test_parse_notebook() {
+        let notebook = Notebook::parse(NOTEBOOK).unwrap();
+        assert_eq!(notebook.language, "python");
+        assert_eq!(notebook.cells.len(), 4);
+        assert_eq!(notebook.cells[0].source, "# Title\nSome *text*");
+        assert_eq!(notebook.cells[2].source, "");
+
+        let notebook = Notebook::parse(r#"{"cells": []}"#).unwrap();
+        assert_eq!(notebook.language, DEFAULT_LANGUAGE);
+
+        assert!(Notebook::parse("not json").is_err());
+    }
+
+    #[test]
+    fn test_layout() {
+        let layout = Notebook::parse(NOTEBOOK).unwrap().layout();
+
+        let positions: Vec<_> = layout
+            .cells
+            .iter()
+            .map(|cell| (cell.language.as_str(), cell.start_line, cell.line_count))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                ("markdown", 0, 2),
+                ("python", 2, 4),
+                ("plain text", 6, 0),
+                ("python", 6, 2),
+            ]
+        );
+        assert_eq!(layout.code.lines().count(), 8);
+        assert_eq!(layout.code.lines().nth(6), Some("class Thing:"));
+    }
+
+    #[test]
+    fn test_symbols_across_cells() {
+        let notebook = Notebook::parse(NOTEBOOK).unwrap();
+        let document = notebook.symbols(&notebook.layout()).unwrap();
+
+        let lines: Vec<_> = document
+            .occurrences
+            .iter()
+            .map(|occ| occ.range[0])
+            .collect();
+        assert!(lines.contains(&4), "def main in the first code cell");
+        assert!(lines.contains(&6), "class Thing in the second code cell");
+    }
+}
@@ -0,0 +1,235 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{anyhow, bail, Context, Result};
+use once_cell::sync::OnceCell;
+use protobuf::Enum;
+use scip::types::SyntaxKind;
+use serde::Deserialize;
+use syntect::parsing::Scope;
+
+/// The mapping that ships with the syntax highlighter. It is used unless a different
+/// mapping is installed with [`install_scope_mapping`] before the first highlight.
+const DEFAULT_SCOPE_MAPPING: &str = include_str!("scope_mapping.json");
+
+/// The only version of the mapping file format that we currently understand.
+const SCOPE_MAPPING_VERSION: u32 = 1;
+
+static SCOPE_MAPPING: OnceCell<ScopeMapping> = OnceCell::new();
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct MappingFile {
+    version: u32,
+    #[serde(default)]
+    ignored: Vec<String>,
+    #[serde(default)]
+    mappings: Vec<MappingRule>,
+    #[serde(default)]
+    languages: HashMap<String, MappingSection>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct MappingSection {
+    #[serde(default)]
+    ignored: Vec<String>,
+    #[serde(default)]
+    mappings: Vec<MappingRule>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct MappingRule {
+    scope: String,
+    kind: String,
+
+    // Free-form explanation for why the rule exists. Ignored when loading.
+    #[serde(default)]
+    #[allow(dead_code)]
+    note: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct ScopeRules {
+    // Whenever a scope matches any of these, it will not emit an occurrence for that range.
+    // The most specific scope that overlaps this region will instead emit an occurrence for
+    // that range (if applicable).
+    ignored: Vec<Scope>,
+
+    // These are IN ORDER. The first scope that is a prefix of the scope being highlighted wins.
+    matches: Vec<(Scope, SyntaxKind)>,
+}
+
+impl ScopeRules {
+    fn from_rules(ignored: &[String], mappings: &[MappingRule]) -> Result<Self> {
+        Ok(Self {
+            ignored: ignored
+                .iter()
+                .map(|s| parse_scope(s))
+                .collect::<Result<_>>()?,
+            matches: mappings
+                .iter()
+                .map(|rule| Ok((parse_scope(&rule.scope)?, parse_syntax_kind(&rule.kind)?)))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    fn is_ignored(&self, scope: &Scope) -> bool {
+        self.ignored
+            .iter()
+            .any(|ignored| ignored.is_prefix_of(*scope))
+    }
+
+    fn match_kind(&self, scope: &Scope) -> Option<SyntaxKind> {
+        self.matches
+            .iter()
+            .find(|&(prefix, _)| prefix.is_prefix_of(*scope))
+            .map(|&(_, kind)| kind)
+    }
+}
+
+/// Declarative table used by the syntect SCIP engine to turn scopes into `SyntaxKind`s.
+///
+/// Rules are grouped in a shared section and in per-language sections. A language section
+/// is keyed by the last atom of a scope, which by TextMate convention names the syntax
+/// that produced it (for example `tsx` for `storage.type.class.tsx`). Language sections
+/// are consulted before the shared section, and within a section rules are applied in the
+/// order they are listed.
+#[derive(Debug)]
+pub struct ScopeMapping {
+    shared: ScopeRules,
+    languages: HashMap<u16, ScopeRules>,
+}
+
+impl ScopeMapping {
+    pub fn from_json(contents: &str) -> Result<Self> {
+        let file: MappingFile =
+            serde_json::from_str(contents).context("invalid scope mapping file")?;
+
+        if file.version != SCOPE_MAPPING_VERSION {
+            bail!(
+                "unsupported scope mapping version {} (expected {})",
+                file.version,
+                SCOPE_MAPPING_VERSION
+            );
+        }
+
+        let mut languages = HashMap::new();
+        for (language, section) in &file.languages {
+            let key = parse_scope(language)?;
+            if key.len() != 1 {
+                bail!("language section {language:?} must be a single scope atom");
+            }
+
+            let rules = ScopeRules::from_rules(&section.ignored, &section.mappings)
+                .with_context(|| format!("in language section {language:?}"))?;
+            languages.insert(key.atom_at(0), rules);
+        }
+
+        Ok(Self {
+            shared: ScopeRules::from_rules(&file.ignored, &file.mappings)?,
+            languages,
+        })
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read scope mapping {}", path.display()))?;
+        Self::from_json(&contents)
+    }
+
+    fn language_rules(&self, scope: &Scope) -> Option<&ScopeRules> {
+        match scope.len() {
+            0 => None,
+            len => self.languages.get(&scope.atom_at(len as usize - 1)),
+        }
+    }
+
+    /// Whether highlights for this scope should be skipped entirely.
+    pub fn should_skip_scope(&self, scope: &Scope) -> bool {
+        self.shared.is_ignored(scope)
+            || self
+                .language_rules(scope)
+                .map_or(false, |rules| rules.is_ignored(scope))
+    }
+
+    /// Maps a scope to a SyntaxKind. Only call this after checking `should_skip_scope`.
+    pub fn match_scope_to_kind(&self, scope: &Scope) -> Option<SyntaxKind> {
+        self.language_rules(scope)
+            .and_then(|rules| rules.match_kind(scope))
+            .or_else(|| self.shared.match_kind(scope))
+    }
+}
+
+/// Installs the mapping used by the syntect SCIP engine. Must be called before the first
+/// document is generated, otherwise the embedded default mapping is already in use.
+pub fn install_scope_mapping(mapping: ScopeMapping) -> Result<()> {
+    SCOPE_MAPPING
+        .set(mapping)
+        .map_err(|_| anyhow!("scope mapping has already been initialized"))
+}
+
+pub fn scope_mapping() -> &'static ScopeMapping {
+    SCOPE_MAPPING.get_or_init(|| {
+        ScopeMapping::from_json(DEFAULT_SCOPE_MAPPING).expect("embedded scope mapping to be valid")
+    })
+}
+
+fn parse_scope(s: &str) -> Result<Scope> {
+    // The only way (as far as I can tell) this can fail is if you pass in a Scope with >=8
+    // atoms, so surface that as a regular error for user provided mappings.
+    Scope::new(s).map_err(|err| anyhow!("invalid scope {s:?}: {err:?}"))
+}
+
+// Only canonical names are accepted, deprecated aliases like `IdentifierKeyword` are not
+// part of `VALUES`. These are the names that show up in the snapshot files.
+fn parse_syntax_kind(s: &str) -> Result<SyntaxKind> {
+    SyntaxKind::VALUES
+        .iter()
+        .find(|kind| format!("{:?}", kind) == s)
+        .copied()
+        .ok_or_else(|| anyhow!("unknown SyntaxKind {s:?}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn scope(s: &str) -> Scope {
+        Scope::new(s).unwrap()
+    }
+
+    #[test]
+    fn test_default_mapping_is_valid() {
+        ScopeMapping::from_json(DEFAULT_SCOPE_MAPPING).expect("default mapping");
+    }
+
+    #[test]
+    fn test_language_sections_take_priority() {
+        let mapping = ScopeMapping::from_json(DEFAULT_SCOPE_MAPPING).unwrap();
+
+        assert_eq!(
+            mapping.match_scope_to_kind(&scope("keyword.operator.expression.keyof.ts")),
+            Some(SyntaxKind::Keyword)
+        );
+        assert_eq!(
+            mapping.match_scope_to_kind(&scope("keyword.operator.arithmetic.ts")),
+            Some(SyntaxKind::IdentifierOperator)
+        );
+        assert!(mapping.should_skip_scope(&scope("meta.tag.tsx")));
+        assert!(!mapping.should_skip_scope(&scope("meta.tag.html")));
+    }
+
+    #[test]
+    fn test_rejects_invalid_mappings() {
+        assert!(ScopeMapping::from_json(r#"{ "version": 2 }"#).is_err());
+        assert!(ScopeMapping::from_json(
+            r#"{ "version": 1, "mappings": [{ "scope": "comment", "kind": "NotAKind" }] }"#
+        )
+        .is_err());
+        assert!(ScopeMapping::from_json(
+            r#"{ "version": 1, "languages": { "source.ts": { "mappings": [] } } }"#
+        )
+        .is_err());
+    }
+}
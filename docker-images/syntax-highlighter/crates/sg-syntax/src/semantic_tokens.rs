@@ -0,0 +1,373 @@
+//! Encodes SCIP documents as LSP semantic tokens.
+//!
+//! See https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_semanticTokens
+//! for the format. Clients need the [`legend`] to decode the token types and modifiers,
+//! which is served from `/semantic-tokens/legend`.
+
+use protobuf::Enum;
+use scip::types::{Document, Occurrence, SymbolRole, SyntaxKind};
+use scip_treesitter::types::PackedRange;
+use serde::Serialize;
+
+/// Token types, in legend order. All of these are standard LSP token types except
+/// for `tag`, which is used for markup tags (e.g. `div` in `<div>`).
+pub const TOKEN_TYPES: &[&str] = &[
+    "namespace",
+    "type",
+    "parameter",
+    "variable",
+    "property",
+    "function",
+    "macro",
+    "keyword",
+    "comment",
+    "string",
+    "number",
+    "regexp",
+    "operator",
+    "tag",
+];
+
+/// Token modifiers, in legend order. All of these are standard LSP token modifiers
+/// except for `local`, which marks symbols that are local to the document.
+pub const TOKEN_MODIFIERS: &[&str] = &[
+    "declaration",
+    "readonly",
+    "static",
+    "defaultLibrary",
+    "local",
+];
+
+const NAMESPACE: u32 = 0;
+const TYPE: u32 = 1;
+const PARAMETER: u32 = 2;
+const VARIABLE: u32 = 3;
+const PROPERTY: u32 = 4;
+const FUNCTION: u32 = 5;
+const MACRO: u32 = 6;
+const KEYWORD: u32 = 7;
+const COMMENT: u32 = 8;
+const STRING: u32 = 9;
+const NUMBER: u32 = 10;
+const REGEXP: u32 = 11;
+const OPERATOR: u32 = 12;
+const TAG: u32 = 13;
+
+const DECLARATION: u32 = 1 << 0;
+const READONLY: u32 = 1 << 1;
+const STATIC: u32 = 1 << 2;
+const DEFAULT_LIBRARY: u32 = 1 << 3;
+const LOCAL: u32 = 1 << 4;
+
+/// `SemanticTokensLegend` from the LSP specification.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticTokensLegend {
+    pub token_types: Vec<&'static str>,
+    pub token_modifiers: Vec<&'static str>,
+}
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Token {
+    token_type: u32,
+    modifiers: u32,
+}
+
+// Punctuation is left out on purpose: editors don't theme it through semantic tokens,
+// and it makes up a large part of all occurrences.
+fn token_type_for_kind(kind: SyntaxKind) -> Option<(u32, u32)> {
+    use SyntaxKind::*;
+
+    Some(match kind {
+        Comment => (COMMENT, 0),
+        Keyword | IdentifierKeyword => (KEYWORD, 0),
+        IdentifierOperator => (OPERATOR, 0),
+        Identifier | IdentifierLocal | IdentifierShadowed => (VARIABLE, 0),
+        IdentifierBuiltin => (VARIABLE, DEFAULT_LIBRARY),
+        IdentifierNull | BooleanLiteral => (KEYWORD, 0),
+        IdentifierConstant => (VARIABLE, READONLY),
+        IdentifierMutableGlobal => (VARIABLE, STATIC),
+        IdentifierParameter => (PARAMETER, 0),
+        IdentifierNamespace | IdentifierModule => (NAMESPACE, 0),
+        IdentifierFunction => (FUNCTION, 0),
+        IdentifierFunctionDefinition => (FUNCTION, DECLARATION),
+        IdentifierMacro => (MACRO, 0),
+        IdentifierMacroDefinition => (MACRO, DECLARATION),
+        IdentifierType => (TYPE, 0),
+        IdentifierBuiltinType => (TYPE, DEFAULT_LIBRARY),
+        IdentifierAttribute | StringLiteralKey | TagAttribute => (PROPERTY, 0),
+        RegexEscape | RegexRepeated | RegexWildcard | RegexDelimiter | RegexJoin => (REGEXP, 0),
+        StringLiteral | StringLiteralEscape | StringLiteralSpecial | CharacterLiteral => {
+            (STRING, 0)
+        }
+        NumericLiteral => (NUMBER, 0),
+        Tag => (TAG, 0),
+        UnspecifiedSyntaxKind | PunctuationDelimiter | PunctuationBracket | TagDelimiter => {
+            return None
+        }
+    })
+}
+
+fn token_for_occurrence(occ: &Occurrence) -> Option<Token> {
+    let kind = occ.syntax_kind.enum_value_or_default();
+    let is_local = occ.symbol.starts_with("local ");
+
+    let (token_type, mut modifiers) = match token_type_for_kind(kind) {
+        Some(token) => token,
+        // Occurrences for locals don't always carry a syntax kind
+        None if is_local => (VARIABLE, 0),
+        None => return None,
+    };
+
+    if is_local {
+        modifiers |= LOCAL;
+    }
+
+    if occ.symbol_roles & SymbolRole::Definition.value() != 0 {
+        modifiers |= DECLARATION;
+    }
+
+    Some(Token {
+        token_type,
+        modifiers,
+    })
+}
+
+// Part of an occurrence on a single line, columns are in characters.
+struct Span {
+    line: usize,
+    start: usize,
+    end: usize,
+    token: Token,
+}
+
+/// Encodes the occurrences of `document` as the `data` array of LSP `SemanticTokens`.
+///
+/// Document ranges are expected to use character offsets, like all our highlighters
+/// produce, and are converted to the default UTF-16 position encoding of LSP.
+/// Tokens spanning multiple lines are split per line, and where occurrences overlap
+/// the innermost one wins.
+pub fn encode(document: &Document, code: &str) -> Vec<u32> {
+    let lines: Vec<&str> = code.lines().collect();
+    let line_lengths: Vec<usize> = lines.iter().map(|l| l.chars().count()).collect();
+    let line_length = |line: usize| line_lengths.get(line).copied().unwrap_or(0);
+
+    let mut spans = vec![];
+    for occ in &document.occurrences {
+        let token = match token_for_occurrence(occ) {
+            Some(token) => token,
+            None => continue,
+        };
+        let range = match PackedRange::from_vec(&occ.range) {
+            Some(range) if range.start_line >= 0 && range.start_col >= 0 => range,
+            _ => continue,
+        };
+
+        for line in range.start_line..=range.end_line {
+            let line = line as usize;
+            let start = if line == range.start_line as usize {
+                range.start_col as usize
+            } else {
+                0
+            };
+            // Clamp to the line, so a bad range can't make us paint past its end
+            let end = if line == range.end_line as usize {
+                (range.end_col.max(0) as usize).min(line_length(line))
+            } else {
+                line_length(line)
+            };
+
+            if start < end {
+                spans.push(Span {
+                    line,
+                    start,
+                    end,
+                    token,
+                });
+            }
+        }
+    }
+
+    // Outer spans come before the spans nested inside of them, so that painting them in
+    // this order lets the inner spans win.
+    spans.sort_by_key(|s| (s.line, s.start, std::cmp::Reverse(s.end)));
+
+    let mut data = vec![];
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+
+    let mut remaining = &spans[..];
+    while let Some(first) = remaining.first() {
+        let line = first.line;
+        let count = remaining.iter().take_while(|s| s.line == line).count();
+        let (line_spans, rest) = remaining.split_at(count);
+        remaining = rest;
+
+        let source_line = lines.get(line).copied().unwrap_or_default();
+        for (start, end, token) in flatten_line(line_spans) {
+            let start = utf16_column(source_line, start);
+            let end = utf16_column(source_line, end);
+            if start >= end {
+                continue;
+            }
+
+            let delta_line = (line - previous_line) as u32;
+            let delta_start = if delta_line == 0 {
+                start - previous_start
+            } else {
+                start
+            };
+
+            data.extend([
+                delta_line,
+                delta_start,
+                end - start,
+                token.token_type,
+                token.modifiers,
+            ]);
+
+            previous_line = line;
+            previous_start = start;
+        }
+    }
+
+    data
+}
+
+// Resolves overlapping spans on a single line into non-overlapping (start, end, token)
+// segments. `spans` must be sorted as in `encode`.
+fn flatten_line(spans: &[Span]) -> Vec<(usize, usize, Token)> {
+    let width = spans.iter().map(|s| s.end).max().unwrap_or(0);
+    let mut owners: Vec<Option<usize>> = vec![None; width];
+    for (idx, span) in spans.iter().enumerate() {
+        owners[span.start..span.end].fill(Some(idx));
+    }
+
+    let mut segments = vec![];
+    let mut column = 0;
+    while column < width {
+        let owner = owners[column];
+        let start = column;
+        while column < width && owners[column] == owner {
+            column += 1;
+        }
+
+        if let Some(idx) = owner {
+            segments.push((start, column, spans[idx].token));
+        }
+    }
+
+    segments
+}
+
+fn utf16_column(line: &str, char_column: usize) -> u32 {
+    line.chars()
+        .take(char_column)
+        .map(|c| c.len_utf16() as u32)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use scip::types::Occurrence;
+
+    use super::*;
+
+    fn occurrence(range: Vec<i32>, kind: SyntaxKind) -> Occurrence {
+        Occurrence {
+            range,
+            syntax_kind: kind.into(),
+            ..Default::default()
+        }
+    }
+
+    fn document(occurrences: Vec<Occurrence>) -> Document {
+        Document {
+            occurrences,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_legend_matches_token_constants() {
+        assert_eq!(TOKEN_TYPES[TAG as usize], "tag");
+        assert_eq!(TOKEN_MODIFIERS.len(), LOCAL.trailing_zeros() as usize + 1);
+    }
+
+    #[test]
+    fn test_delta_encoding() {
+        let code = "func main() {\n\treturn 1\n}\n";
+        let doc = document(vec![
+            occurrence(vec![0, 0, 4], SyntaxKind::Keyword),
+            occurrence(vec![0, 5, 9], SyntaxKind::IdentifierFunction),
+            occurrence(vec![0, 9, 10], SyntaxKind::PunctuationBracket),
+            occurrence(vec![1, 1, 7], SyntaxKind::Keyword),
+            occurrence(vec![1, 8, 9], SyntaxKind::NumericLiteral),
+        ]);
+
+        assert_eq!(
+            encode(&doc, code),
+            vec![
+                0, 0, 4, KEYWORD, 0, //
+                0, 5, 4, FUNCTION, 0, //
+                1, 1, 6, KEYWORD, 0, //
+                0, 7, 1, NUMBER, 0,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_and_multiline_occurrences() {
+        let code = "x = `a${b}c\nd`";
+        let doc = document(vec![
+            occurrence(vec![0, 4, 1, 2], SyntaxKind::StringLiteral),
+            occurrence(vec![0, 8, 9], SyntaxKind::Identifier),
+        ]);
+
+        assert_eq!(
+            encode(&doc, code),
+            vec![
+                0, 4, 4, STRING, 0, //
+                0, 4, 1, VARIABLE, 0, //
+                0, 1, 2, STRING, 0, //
+                1, 0, 2, STRING, 0,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_utf16_columns_and_locals() {
+        let code = "😀 = x";
+        let mut local = occurrence(vec![0, 4, 5], SyntaxKind::UnspecifiedSyntaxKind);
+        local.symbol = "local 1".to_string();
+        local.symbol_roles = SymbolRole::Definition.value();
+
+        let doc = document(vec![
+            occurrence(vec![0, 0, 1], SyntaxKind::Identifier),
+            local,
+        ]);
+
+        assert_eq!(
+            encode(&doc, code),
+            vec![
+                0,
+                0,
+                2,
+                VARIABLE,
+                0, //
+                0,
+                5,
+                1,
+                VARIABLE,
+                DECLARATION | LOCAL,
+            ]
+        );
+    }
+}
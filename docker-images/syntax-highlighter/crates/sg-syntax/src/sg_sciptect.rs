@@ -8,184 +8,12 @@ use syntect::{
     util::LinesWithEndings,
 };
 
+use crate::scope_mapping::scope_mapping;
+
 static EMPTY_SCOPE: OnceCell<Scope> = OnceCell::new();
 fn empty_scope() -> Scope {
     *EMPTY_SCOPE.get_or_init(|| Scope::new("").unwrap())
 }
-// Whenever a scope matches any of the scopes in IGNORED_SCOPES,
-// it will not emit an occurrence for that range. The most specific
-// scope that overlaps this region will instead emit an occurrence for
-// that range (if applicable).
-static IGNORED_SCOPES: OnceCell<Vec<Scope>> = OnceCell::new();
-fn should_skip_scope(scope: &Scope) -> bool {
-    IGNORED_SCOPES
-        .get_or_init(|| {
-            // See match_scope_to_kind
-            let scope = |s| Scope::new(s).unwrap();
-            vec![
-                scope("source"),
-                scope("meta.tag.tsx"),
-                scope("meta.tag.without-attributes.tsx"),
-                scope("punctuation.definition.tag.begin.tsx"),
-                scope("punctuation.definition.tag.end.tsx"),
-                scope("entity.other.document.begin.yaml"),
-                scope("punctuation.definition.heading.begin"),
-                scope("punctuation.definition.heading.end"),
-                scope("punctuation.definition.bold.begin"),
-                scope("punctuation.definition.bold.end"),
-                scope("punctuation.definition.italic.begin"),
-                scope("punctuation.definition.italic.end"),
-                scope("punctuation.definition.strikethrough.begin"),
-                scope("punctuation.definition.strikethrough.end"),
-                scope("punctuation.definition.raw.begin"),
-                scope("punctuation.definition.raw.end"),
-                scope("punctuation.definition.link.begin"),
-                scope("punctuation.definition.link.end"),
-                scope("punctuation.definition.metadata.begin"),
-                scope("punctuation.definition.metadata.end"),
-                scope("punctuation.definition.string.begin"),
-                scope("punctuation.definition.string.end"),
-                scope("punctuation.definition.comment"),
-            ]
-        })
-        .iter()
-        .any(|ignored| ignored.is_prefix_of(*scope))
-}
-
-// Maps scopes to SyntaxKind. Runs after checking if a scope is in IGNORED_SCOPES
-static SCOPE_MATCHES: OnceCell<Vec<(Scope, SyntaxKind)>> = OnceCell::new();
-
-#[rustfmt::skip]
-fn match_scope_to_kind(scope: &Scope) -> Option<SyntaxKind> {
-    let scope_matches: &Vec<(Scope, SyntaxKind)> = SCOPE_MATCHES.get_or_init(|| {
-        use SyntaxKind::*;
-
-        // TODO: We should probably make sure that we can't even ship syntax-highlighter if this
-        // doesn't work (which should happen because it won't be able to pass tests or do anything
-        // without this)
-        //
-        // The only way (as far as I can tell) this can fail is if you pass in a Scope with >=8
-        // atoms (so we just won't do that here). This only runs once, so we don't have to worry
-        // about subsequent failures for any of these unwraps.
-        let scope = |s| Scope::new(s).unwrap();
-
-        // These are IN ORDER.
-        //  If you want something to resolve to something more specifically or as a higher priority
-        //  make sure to place the scope(...) at the beginning of the list.
-        vec![
-            (scope("comment"), Comment),
-            (scope("meta.documentation"), Comment),
-            // TODO: How does this play with this: keyword.control.import.include
-            (scope("meta.preprocessor.include"), IdentifierNamespace),
-            (scope("storage.type.keyword"), IdentifierKeyword),
-            (scope("entity.name.function"), IdentifierFunction),
-            (scope("entity.name.type"), IdentifierType),
-            // TODO: optimization opportunity, skip testing language-specific scopes.
-            (
-                scope("keyword.operator.expression.keyof.ts"),
-                IdentifierKeyword,
-            ),
-            (
-                scope("keyword.operator.expression.keyof.tsx"),
-                IdentifierKeyword,
-            ),
-            (
-                scope("keyword.operator.expression.typeof.ts"),
-                IdentifierKeyword,
-            ),
-            (
-                scope("keyword.operator.expression.typeof.tsx"),
-                IdentifierKeyword,
-            ),
-            (scope("storage.type.namespace.ts"), IdentifierKeyword),
-            (scope("storage.type.namespace.tsx"), IdentifierKeyword),
-            (scope("storage.type.module.js"), IdentifierKeyword),
-            (scope("storage.type.module.jsx"), IdentifierKeyword),
-            (scope("storage.type.module.ts"), IdentifierKeyword),
-            (scope("storage.type.module.tsx"), IdentifierKeyword),
-            (scope("storage.type.interface.ts"), IdentifierKeyword),
-            (scope("storage.type.interface.tsx"), IdentifierKeyword),
-            (scope("storage.type.class.ts"), IdentifierKeyword),
-            (scope("storage.type.class.tsx"), IdentifierKeyword),
-            (scope("storage.type.class.js"), IdentifierKeyword),
-            (scope("storage.type.class.jsx"), IdentifierKeyword),
-            (scope("storage.type.type.tsx"), IdentifierKeyword),
-            (scope("storage.type.type.ts"), IdentifierKeyword),
-            (scope("storage.type.enum.tsx"), IdentifierKeyword),
-            (scope("storage.type.enum.ts"), IdentifierKeyword),
-            (scope("storage.type.function.tsx"), IdentifierKeyword),
-            (scope("storage.type.function.ts"), IdentifierKeyword),
-            (scope("storage.type.function.js"), IdentifierKeyword),
-            (scope("storage.type.function.jsx"), IdentifierKeyword),
-            (scope("storage.type.ts"), IdentifierKeyword),
-            (scope("storage.type.tsx"), IdentifierKeyword),
-            (scope("keyword.operator.logical.sql"), IdentifierKeyword),
-            (
-                scope("keyword.operator.assignment.alias.sql"),
-                IdentifierKeyword,
-            ),
-            (scope("meta.mapping.key.json"), StringLiteralKey),
-            (scope("entity.name.tag.yaml"), StringLiteralKey),
-            (scope("entity.other.attribute-name.class.css"), Identifier),
-            (scope("meta.link.inline.markdown"), StringLiteral),
-            (scope("storage.type.scala"), IdentifierKeyword),
-            (scope("storage.type.stable.scala"), IdentifierKeyword),
-            (scope("storage.type.class.scala"), IdentifierKeyword),
-            (scope("storage.type.function.scala"), IdentifierKeyword),
-            (scope("storage.type.volatile.scala"), IdentifierKeyword),
-            // (scope("entity.name.section.markdown"), IdentifierType),
-            (scope("meta.tag"), Identifier),
-            (scope("markup.bold"), Identifier),
-            (scope("markup.underline"), Identifier),
-            (scope("markup.italic"), Identifier),
-            (scope("markup.raw"), StringLiteral),
-            (scope("markup.heading"), Identifier),
-            (scope("keyword.operator"), IdentifierOperator),
-            (scope("keyword"), IdentifierKeyword),
-            (scope("variable.language.this"), IdentifierBuiltin),
-            (scope("variable.function"), IdentifierFunction),
-            (scope("meta.definition.property"), IdentifierAttribute),
-            (scope("variable"), Identifier),
-            (scope("constant.character.escape"), StringLiteralEscape),
-            (scope("string"), StringLiteral),
-            (scope("constant.numeric"), NumericLiteral),
-            (scope("constant.character"), CharacterLiteral),
-            (scope("constant.language"), IdentifierBuiltin),
-            (scope("storage.modifier.array"), PunctuationBracket),
-            (scope("storage.modifier"), IdentifierKeyword),
-            (scope("storage.type.namespace"), IdentifierNamespace),
-            (scope("storage.type.ts"), IdentifierKeyword),
-            (scope("storage.type"), IdentifierType),
-            (scope("support.type.builtin"), IdentifierBuiltinType),
-            (scope("meta.object-literal.key"), IdentifierAttribute),
-            (scope("meta.path"), IdentifierNamespace),
-            // (scope("meta.type"), IdentifierType), Intentionally disabled in favor of more precise classes
-            (scope("meta.return.type"), IdentifierType),
-            (scope("support.type"), IdentifierType),
-            (scope("support.class"), IdentifierType),
-            (scope("support.function"), IdentifierFunction),
-            (scope("support.variable"), Identifier),
-            (scope("entity.other.attribute-name"), TagAttribute),
-            (scope("entity.name"), Identifier),
-            (scope("entity.other"), Identifier),
-            //
-            // Punctuation Types: while these may appear noisy, they're
-            // intentionally included so that punctutation characters get
-            // correctly highlighted when nested inside other occurrences like
-            // interpolated string literals. Example: the braces in `a${b}`.
-            (scope("punctuation.section.mapping"), PunctuationBracket),
-            (scope("punctuation.section.sequence"), PunctuationBracket),
-            (scope("punctuation.terminator"), PunctuationDelimiter),
-            (scope("meta.brace"), PunctuationBracket),
-            (scope("punctuation"), PunctuationBracket),
-        ]
-    });
-
-    scope_matches
-        .iter()
-        .find(|&(prefix, _)| prefix.is_prefix_of(*scope))
-        .map(|&(_, kind)| kind)
-}
 
 /// The DocumentGenerator generate a Document with occurrences set to the corresponding syntax kinds
 ///
@@ -346,7 +174,14 @@ impl<'a> DocumentGenerator<'a> {
     }
 
     // generate takes ownership of self so that it can't be re-used
-    pub fn generate(mut self) -> Document {
+    pub fn generate(self) -> Document {
+        self.generate_with_unhandled_scopes().0
+    }
+
+    /// Same as `generate`, but also returns the scopes that were neither ignored nor
+    /// mapped to a SyntaxKind by the scope mapping.
+    pub fn generate_with_unhandled_scopes(mut self) -> (Document, HashSet<Scope>) {
+        let mapping = scope_mapping();
         let mut document = Document::default();
 
         let mut stack = ScopeStack::new();
@@ -381,12 +216,12 @@ impl<'a> DocumentGenerator<'a> {
                         BasicScopeStackOp::Push(scope) => {
                             // We have to push PartialHighight to the stack
                             // so that when we come to `pop` these highlights they still pop.
-                            if should_skip_scope(&scope) {
+                            if mapping.should_skip_scope(&scope) {
                                 highlight_manager.push_empty();
                                 return;
                             }
 
-                            match match_scope_to_kind(&scope) {
+                            match mapping.match_scope_to_kind(&scope) {
                                 Some(kind) => {
                                     // Uncomment to debug what scopes are picked up
                                     // println!("SCOPE {row:>3}:{character:<3} {:50} {kind:?}", format!("{}", scope));
@@ -469,7 +304,7 @@ impl<'a> DocumentGenerator<'a> {
             }
         }
 
-        document
+        (document, unhandled_scopes)
     }
 }
 
@@ -606,6 +441,7 @@ mod test {
                 filetype: None,
                 line_length_limit: None,
                 code: contents.clone(),
+                ..Default::default()
             };
             let syntax_def = determine_language(&q, &ss).unwrap();
             let document = DocumentGenerator::new(&ss, syntax_def, &q.code, None).generate();
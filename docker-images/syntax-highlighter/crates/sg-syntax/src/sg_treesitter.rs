@@ -2,15 +2,21 @@ use anyhow::Result;
 use protobuf::Message;
 use rocket::serde::json::{serde_json::json, Value as JsonValue};
 use scip::types::{Document, Occurrence, SyntaxKind};
-use scip_treesitter::types::PackedRange;
-use scip_treesitter_languages::highlights::{
-    get_highlighting_configuration, get_syntax_kind_for_hl,
-};
-use tree_sitter_highlight::{
-    Error, Highlight, HighlightConfiguration, HighlightEvent, Highlighter as TSHighlighter,
+use scip_treesitter::{diagnostics::syntax_errors, types::PackedRange};
+use scip_treesitter_languages::{
+    highlights::{get_highlighting_configuration, get_syntax_kind_for_hl},
+    parsers::BundledParser,
+    pool,
 };
+use tree_sitter::Tree;
+use tree_sitter_highlight::{Error, Highlight, HighlightConfiguration, HighlightEvent};
 
-use crate::SourcegraphQuery;
+use crate::{
+    classify::{classify, skip_policy},
+    decode_input,
+    fenced_code::{self, FencedBlock},
+    SourcegraphQuery,
+};
 
 // Handle special cases where syntect language names don't match treesitter names.
 pub fn treesitter_language(syntect_language: &str) -> &str {
@@ -26,17 +32,29 @@ pub fn jsonify_err(e: impl ToString) -> JsonValue {
 
 // TODO(cleanup_lsif): Remove this when we remove /lsif endpoint
 // Currently left unchanged
-pub fn lsif_highlight(q: SourcegraphQuery) -> Result<JsonValue, JsonValue> {
+pub fn lsif_highlight(mut q: SourcegraphQuery) -> Result<JsonValue, JsonValue> {
+    let input = decode_input(&mut q.code, &q.code_base64, &q.charset)?;
     let filetype = q
         .filetype
         .ok_or_else(|| json!({"error": "Must pass a filetype for /lsif" }))?
         .to_lowercase();
 
-    match index_language(&filetype, &q.code, false) {
+    let flags = classify(&q.filepath, &q.code);
+    if skip_policy().should_skip(&flags) {
+        let encoded = Document::new().write_to_bytes().map_err(jsonify_err)?;
+        return Ok(json!({"data": base64::encode(encoded), "plaintext": true, "flags": flags}));
+    }
+
+    match index_language(&filetype, &q.code, false, false) {
         Ok(document) => {
             let encoded = document.write_to_bytes().map_err(jsonify_err)?;
 
-            Ok(json!({"data": base64::encode(encoded), "plaintext": false}))
+            let mut response =
+                json!({"data": base64::encode(encoded), "plaintext": false, "flags": flags});
+            if let Some(input) = &input {
+                response["encoding"] = input.to_json();
+            }
+            Ok(response)
         }
         Err(Error::InvalidLanguage) => Err(json!({
             "error": format!("{} is not a valid filetype for treesitter", filetype)
@@ -45,11 +63,20 @@ pub fn lsif_highlight(q: SourcegraphQuery) -> Result<JsonValue, JsonValue> {
     }
 }
 
-pub fn index_language(filetype: &str, code: &str, include_locals: bool) -> Result<Document, Error> {
+pub fn index_language(
+    filetype: &str,
+    code: &str,
+    include_locals: bool,
+    include_diagnostics: bool,
+) -> Result<Document, Error> {
     match get_highlighting_configuration(filetype) {
-        Some(lang_config) => {
-            index_language_with_config(filetype, code, lang_config, include_locals)
-        }
+        Some(lang_config) => index_language_with_config(
+            filetype,
+            code,
+            lang_config,
+            include_locals,
+            include_diagnostics,
+        ),
         None => Err(Error::InvalidLanguage),
     }
 }
@@ -59,73 +86,436 @@ pub fn index_language_with_config(
     code: &str,
     lang_config: &HighlightConfiguration,
     include_locals: bool,
+    include_diagnostics: bool,
 ) -> Result<Document, Error> {
     // Normalize string to be always only \n endings.
     //  We don't care that the byte offsets are "incorrect" now for this
     //  because we are using a line,col based approach
     let code = code.replace("\r\n", "\n");
 
+    // tree-sitter-highlight doesn't hand out the tree it parsed, but locals and
+    // diagnostics share one.
+    let tree = if include_locals || include_diagnostics {
+        parse_normalized(filetype, &code)
+    } else {
+        None
+    };
+
+    index_code(
+        filetype,
+        &code,
+        lang_config,
+        tree.as_ref(),
+        include_locals,
+        include_diagnostics,
+    )
+}
+
+/// Like `index_language`, but uses `tree` for locals and diagnostics instead of parsing
+/// `code` again. `tree` must come from `parse_tree` for the same filetype and code.
+pub fn index_language_with_tree(
+    filetype: &str,
+    code: &str,
+    tree: &Tree,
+    include_locals: bool,
+    include_diagnostics: bool,
+) -> Result<Document, Error> {
+    let lang_config = get_highlighting_configuration(filetype).ok_or(Error::InvalidLanguage)?;
+    let code = code.replace("\r\n", "\n");
+
+    index_code(
+        filetype,
+        &code,
+        lang_config,
+        Some(tree),
+        include_locals,
+        include_diagnostics,
+    )
+}
+
+// `code` must only have \n line endings, `tree` is needed for locals and diagnostics.
+fn index_code(
+    filetype: &str,
+    code: &str,
+    lang_config: &HighlightConfiguration,
+    tree: Option<&Tree>,
+    include_locals: bool,
+    include_diagnostics: bool,
+) -> Result<Document, Error> {
+    let mut doc = highlight(code, lang_config)?;
+
+    if include_locals {
+        if let (Some(parser), Some(tree)) = (BundledParser::get_parser(filetype), tree) {
+            let locals = scip_syntax::get_locals_for_tree(parser, tree, code.as_bytes())
+                .unwrap_or(Ok(vec![]))
+                .unwrap_or_default();
+
+            doc.occurrences = merge_locals(std::mem::take(&mut doc.occurrences), locals, code);
+        }
+    }
+
+    if let Some(doc_comments) = doc_comments(filetype) {
+        doc.occurrences = highlight_comment_blocks(
+            filetype,
+            code,
+            &doc_comments,
+            std::mem::take(&mut doc.occurrences),
+        );
+    }
+
+    if include_diagnostics {
+        if let Some(tree) = tree {
+            // The highlight occurrences use character based columns, so we can't
+            // use the byte based ranges from tree-sitter directly.
+            let offsets = OffsetManager::new(code)?;
+            for error in syntax_errors(tree.root_node(), code.as_bytes()) {
+                let mut occurrence = Occurrence::new();
+                occurrence.range = offsets.range(error.node.start_byte(), error.node.end_byte());
+                occurrence.diagnostics.push(error.to_diagnostic());
+                doc.occurrences.push(occurrence);
+            }
+
+            doc.occurrences.sort_by_key(|a| (a.range[0], a.range[1]));
+        }
+    }
+
+    Ok(doc)
+}
+
+fn highlight(code: &str, lang_config: &HighlightConfiguration) -> Result<Document, Error> {
     // TODO: We should automatically apply no highlights when we are
     // in an injected piece of code.
     //
     // Unfortunately, that information isn't currently available when
     // we are iterating in the higlighter.
-    let mut highlighter = TSHighlighter::new();
+    let mut highlighter = pool::highlighter();
     let highlights = highlighter.highlight(lang_config, code.as_bytes(), None, |l| {
         get_highlighting_configuration(l)
     })?;
 
     let mut emitter = ScipEmitter::new();
-    let mut doc = emitter.render(highlights, &code, &get_syntax_kind_for_hl)?;
+    let mut doc = emitter.render(highlights, code, &get_syntax_kind_for_hl)?;
     doc.occurrences.sort_by_key(|a| (a.range[0], a.range[1]));
 
-    if include_locals {
-        let parser = scip_treesitter_languages::parsers::BundledParser::get_parser(filetype);
-        if let Some(parser) = parser {
-            // TODO: Could probably write this in a much better way.
-            let mut local_occs = scip_syntax::get_locals(parser, code.as_bytes())
-                .unwrap_or(Ok(vec![]))
-                .unwrap_or_default();
+    Ok(doc)
+}
 
-            // Get ranges in reverse order, because we're going to pop off the back of the list.
-            //  (that's why we're sorting the opposite way of the document occurrences above).
-            local_occs.sort_by_key(|a| (-a.range[0], -a.range[1]));
-
-            let mut next_doc_idx = 0;
-            while let Some(local) = local_occs.pop() {
-                // We *should* be able to assume that all these ranges are valid ranges
-                // but for now we'll skip if they aren't.
-                //
-                // We can add some observability stuff to this later, and/or make
-                // certain builds fail or something to test this out better (but
-                // not have syntax highlighting completely fall apart from one
-                // bad range)
-                let local_range = match PackedRange::from_vec(&local.range) {
-                    Some(range) => range,
-                    None => continue,
-                };
+/// Highlights the contents of a fenced code block with tree-sitter. The occurrences are
+/// relative to the block, see [`FencedBlock::remap_occurrences`].
+///
+/// Returns None if we don't have a parser for the language of the block.
+pub(crate) fn highlight_fenced_block(
+    block: &FencedBlock,
+    default: Option<&str>,
+) -> Option<Document> {
+    let parser = block.parser(default)?;
+    let lang_config = get_highlighting_configuration(parser.get_language_name())?;
+    highlight(&block.code(), lang_config).ok()
+}
 
-                let (matching_idx, matching_occ) = match doc
-                    .occurrences
-                    .iter_mut()
-                    .enumerate()
-                    .skip(next_doc_idx)
-                    .find(|(_, occ)| local_range.eq_vec(&occ.range))
-                {
-                    Some(found) => found,
-                    None => continue,
+// The doc comments of a language, which can contain fenced code blocks. Plain comments
+// are left alone, they are rarely Markdown.
+struct DocComments {
+    // How doc comments start, e.g. `/**`
+    openers: &'static [&'static str],
+
+    // Prefixes of the comment lines, including the `*` that continues a `/**` comment.
+    // Longer prefixes have to come first, so that `///` isn't taken for `//` followed
+    // by a `/`.
+    prefixes: &'static [&'static str],
+}
+
+fn doc_comments(filetype: &str) -> Option<DocComments> {
+    match filetype {
+        // rustdoc and Doxygen
+        "c" | "cpp" | "rust" => Some(DocComments {
+            openers: &["///", "//!", "/**", "/*!"],
+            prefixes: &["///", "//!", "/**", "/*!", "*"],
+        }),
+        "c_sharp" => Some(DocComments {
+            openers: &["///"],
+            prefixes: &["///"],
+        }),
+        "zig" => Some(DocComments {
+            openers: &["///", "//!"],
+            prefixes: &["///", "//!"],
+        }),
+        // Javadoc, JSDoc, KDoc and Scaladoc
+        "java" | "javascript" | "kotlin" | "scala" | "typescript" | "tsx" => Some(DocComments {
+            openers: &["/**"],
+            prefixes: &["/**", "*"],
+        }),
+        _ => None,
+    }
+}
+
+// Highlights fenced code blocks in the doc comments of `code` as the language of their
+// fence. The comment occurrences are split around the occurrences of the code.
+fn highlight_comment_blocks(
+    filetype: &str,
+    code: &str,
+    doc_comments: &DocComments,
+    occurrences: Vec<Occurrence>,
+) -> Vec<Occurrence> {
+    // Highlight occurrences don't overlap, so the comments are sorted by start and end
+    let comments: Vec<PackedRange> = occurrences
+        .iter()
+        .filter(|occ| occ.syntax_kind.enum_value_or_default() == SyntaxKind::Comment)
+        .filter_map(|occ| PackedRange::from_vec(&occ.range))
+        .collect();
+
+    let lines: Vec<&str> = code.lines().collect();
+    let is_doc_comment = |line: usize, column: usize| {
+        let position = (line as i32, column as i32);
+        let idx = comments.partition_point(|comment| end_of(comment) <= position);
+        comments
+            .get(idx)
+            .filter(|comment| start_of(comment) <= position)
+            .map_or(false, |comment| {
+                let text = line_from(&lines, start_of(comment));
+                doc_comments
+                    .openers
+                    .iter()
+                    .any(|opener| text.starts_with(opener))
+            })
+    };
+
+    let blocks = fenced_code::comment_blocks(code, doc_comments.prefixes, is_doc_comment);
+    if blocks.is_empty() {
+        return occurrences;
+    }
+
+    // Only rustdoc treats blocks without a language as code of the documented language
+    let default = (filetype == "rust").then_some(filetype);
+
+    let mut additions = vec![];
+    for block in &blocks {
+        if let Some(inner) = highlight_fenced_block(block, default) {
+            additions.extend(block.remap_occurrences(inner));
+        }
+    }
+
+    let cuts = additions
+        .iter()
+        .filter_map(|occ| PackedRange::from_vec(&occ.range))
+        .collect();
+
+    fenced_code::splice_occurrences(occurrences, cuts, additions)
+}
+
+// The text of a line from a character based column on
+fn line_from<'a>(lines: &[&'a str], (line, column): Position) -> &'a str {
+    let text = lines.get(line as usize).copied().unwrap_or_default();
+    let start = text
+        .char_indices()
+        .nth(column as usize)
+        .map_or(text.len(), |(idx, _)| idx);
+    &text[start..]
+}
+
+pub(crate) type Position = (i32, i32);
+
+pub(crate) fn start_of(range: &PackedRange) -> Position {
+    (range.start_line, range.start_col)
+}
+
+pub(crate) fn end_of(range: &PackedRange) -> Position {
+    (range.end_line, range.end_col)
+}
+
+pub(crate) fn range_between(start: Position, end: Position) -> PackedRange {
+    PackedRange {
+        start_line: start.0,
+        start_col: start.1,
+        end_line: end.0,
+        end_col: end.1,
+    }
+}
+
+// Locals use tree-sitter's byte based columns, highlights use character based columns.
+fn to_char_columns(range: PackedRange, lines: &[&str]) -> PackedRange {
+    let column = |line: i32, col: i32| {
+        lines
+            .get(line as usize)
+            .and_then(|l| l.get(..col as usize))
+            .map_or(col, |prefix| prefix.chars().count() as i32)
+    };
+
+    PackedRange {
+        start_col: column(range.start_line, range.start_col),
+        end_col: column(range.end_line, range.end_col),
+        ..range
+    }
+}
+
+/// Merges local definitions and references into the (sorted, non-overlapping) highlight
+/// occurrences with an ordered interval join, so that every local ends up in the document:
+///
+/// - A local inside of a highlight occurrence splits it, and the piece covering the local
+///   gets the local's symbol. For an exact match that piece is the whole occurrence.
+/// - Any other local (spanning several highlights, or none) is inserted as an occurrence
+///   of its own, without a syntax kind.
+fn merge_locals(
+    highlights: Vec<Occurrence>,
+    locals: Vec<Occurrence>,
+    code: &str,
+) -> Vec<Occurrence> {
+    let lines: Vec<&str> = code.split('\n').collect();
+    let mut locals: Vec<(PackedRange, Occurrence)> = locals
+        .into_iter()
+        .filter_map(|local| {
+            let range = PackedRange::from_vec(&local.range)?;
+            Some((to_char_columns(range, &lines), local))
+        })
+        .collect();
+    locals.sort_by_key(|(range, _)| (start_of(range), end_of(range)));
+
+    let mut merged = Vec::with_capacity(highlights.len() + locals.len());
+    let mut highlights = highlights.into_iter();
+
+    // The highlight that is being joined, or what is left of it after splits
+    let mut current: Option<(PackedRange, Occurrence)> = None;
+
+    for (local_range, local) in locals {
+        // Everything that ends before this local can't overlap it, or any later local
+        loop {
+            if current.is_none() {
+                current = match highlights.next() {
+                    Some(occ) => match PackedRange::from_vec(&occ.range) {
+                        Some(range) => Some((range, occ)),
+                        None => {
+                            merged.push(occ);
+                            continue;
+                        }
+                    },
+                    None => None,
                 };
+            }
+
+            let ends_before_local = matches!(
+                &current,
+                Some((range, _)) if end_of(range) <= start_of(&local_range)
+            );
+            if !ends_before_local {
+                break;
+            }
+
+            merged.extend(current.take().map(|(_, occ)| occ));
+        }
 
-                next_doc_idx = matching_idx;
+        match current.take() {
+            Some((range, occ)) if range.contains(&local_range) => {
+                if start_of(&range) < start_of(&local_range) {
+                    merged.push(Occurrence {
+                        range: range_between(start_of(&range), start_of(&local_range)).to_vec(),
+                        ..occ.clone()
+                    });
+                }
 
-                // Update occurrence with new information from locals
-                matching_occ.symbol = local.symbol;
-                matching_occ.symbol_roles = local.symbol_roles;
+                merged.push(Occurrence {
+                    range: local_range.to_vec(),
+                    symbol: local.symbol,
+                    symbol_roles: local.symbol_roles,
+                    ..occ.clone()
+                });
+
+                if end_of(&local_range) < end_of(&range) {
+                    let rest = range_between(end_of(&local_range), end_of(&range));
+                    current = Some((
+                        rest,
+                        Occurrence {
+                            range: rest.to_vec(),
+                            ..occ
+                        },
+                    ));
+                }
+            }
+            other => {
+                current = other;
+                merged.push(Occurrence {
+                    range: local_range.to_vec(),
+                    symbol: local.symbol,
+                    symbol_roles: local.symbol_roles,
+                    ..Default::default()
+                });
             }
         }
     }
 
-    Ok(doc)
+    merged.extend(current.map(|(_, occ)| occ));
+    merged.extend(highlights);
+
+    // Inserted locals may start before the highlight they were compared against
+    merged.sort_by_key(|occ| {
+        let range = PackedRange::from_vec(&occ.range).unwrap_or_default();
+        start_of(&range)
+    });
+
+    merged
+}
+
+/// Parses `code` with the tree-sitter parser for the filetype, after normalizing line
+/// endings the same way as `index_language`.
+///
+/// Returns None if there is no tree-sitter parser for the filetype.
+pub fn parse_tree(filetype: &str, code: &str) -> Option<Tree> {
+    parse_normalized(filetype, &code.replace("\r\n", "\n"))
+}
+
+fn parse_normalized(filetype: &str, code: &str) -> Option<Tree> {
+    let bundled_parser = BundledParser::get_parser(filetype)?;
+    pool::parser(bundled_parser).parse(code, None)
+}
+
+/// Parses `code` and returns its `error_ratio`.
+///
+/// Returns None if there is no tree-sitter parser for the filetype.
+pub fn parse_error_ratio(filetype: &str, code: &str) -> Option<f64> {
+    parse_tree(filetype, code).map(|tree| error_ratio(&tree))
+}
+
+/// The fraction of nodes in the tree that are `ERROR` or `MISSING` nodes, or that are
+/// nested inside of an `ERROR` node.
+pub fn error_ratio(tree: &Tree) -> f64 {
+    let root = tree.root_node();
+    if !root.has_error() {
+        return 0.0;
+    }
+
+    let mut total = 0;
+    let mut errors = 0;
+
+    // Number of ERROR nodes between the root and the current node
+    let mut error_ancestors = 0;
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        total += 1;
+        if error_ancestors > 0 || node.is_error() || node.is_missing() {
+            errors += 1;
+        }
+
+        if cursor.goto_first_child() {
+            if node.is_error() {
+                error_ancestors += 1;
+            }
+            continue;
+        }
+
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+
+            if !cursor.goto_parent() {
+                return errors as f64 / total as f64;
+            }
+
+            if cursor.node().is_error() {
+                error_ancestors -= 1;
+            }
+        }
+    }
 }
 
 struct OffsetManager {
@@ -295,7 +685,7 @@ mod test {
     #[test]
     fn test_highlights_one_comment() -> Result<(), Error> {
         let src = "// Hello World";
-        let document = index_language("go", src, false)?;
+        let document = index_language("go", src, false, false)?;
         insta::assert_snapshot!(snapshot_treesitter_syntax_kinds(&document, src));
 
         Ok(())
@@ -310,7 +700,7 @@ SELECT * FROM my_table
 `
 "#;
 
-        let document = index_language("go", src, false)?;
+        let document = index_language("go", src, false, false)?;
         insta::assert_snapshot!(snapshot_treesitter_syntax_kinds(&document, src));
 
         Ok(())
@@ -319,12 +709,243 @@ SELECT * FROM my_table
     #[test]
     fn test_highlight_csharp_file() -> Result<(), Error> {
         let src = "using System;";
-        let document = index_language("c_sharp", src, false)?;
+        let document = index_language("c_sharp", src, false, false)?;
         insta::assert_snapshot!(snapshot_treesitter_syntax_kinds(&document, src));
 
         Ok(())
     }
 
+    #[test]
+    fn test_diagnostics_for_syntax_errors() -> Result<(), Error> {
+        let src = "package main\n\nfunc main() {\n\tx := \n";
+        let document = index_language("go", src, false, true)?;
+
+        let diagnostics: Vec<_> = document
+            .occurrences
+            .iter()
+            .flat_map(|occ| occ.diagnostics.iter())
+            .collect();
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.severity == scip::types::Severity::Error.into()));
+
+        let document = index_language("go", src, false, false)?;
+        assert!(document
+            .occurrences
+            .iter()
+            .all(|occ| occ.diagnostics.is_empty()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_highlights_code_blocks_in_doc_comments() -> Result<(), Error> {
+        let src = "/// ```\n/// let x = 1;\n/// ```\nfn f() {}\n";
+        let document = index_language("rust", src, false, false)?;
+
+        let kinds: Vec<_> = document
+            .occurrences
+            .iter()
+            .filter(|occ| occ.range[0] == 1)
+            .map(|occ| (occ.range.clone(), occ.syntax_kind.enum_value_or_default()))
+            .collect();
+
+        assert_eq!(kinds.first(), Some(&(vec![1, 0, 4], SyntaxKind::Comment)));
+        assert!(kinds.contains(&(vec![1, 4, 7], SyntaxKind::Keyword)));
+        assert!(kinds.contains(&(vec![1, 12, 13], SyntaxKind::NumericLiteral)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_only_doc_comments_have_code_blocks() -> Result<(), Error> {
+        // The code before the comments has no keywords in JavaScript
+        let has_keyword = |filetype, src| -> Result<bool, Error> {
+            let document = index_language(filetype, src, false, false)?;
+            Ok(document.occurrences.iter().any(|occ| {
+                occ.range[0] > 0 && occ.syntax_kind.enum_value_or_default() == SyntaxKind::Keyword
+            }))
+        };
+
+        // Plain comments
+        assert!(!has_keyword(
+            "rust",
+            "fn f() {}\n// ```rust\n// let x = 1;\n// ```\n"
+        )?);
+        assert!(!has_keyword(
+            "javascript",
+            "f();\n/*\n * ```js\n * let x = 1;\n * ```\n */\n"
+        )?);
+
+        assert!(has_keyword(
+            "javascript",
+            "f();\n/**\n * ```js\n * let x = 1;\n * ```\n */\n"
+        )?);
+
+        // Only rustdoc defaults to the language of the file
+        assert!(!has_keyword(
+            "javascript",
+            "f();\n/**\n * ```\n * let x = 1;\n * ```\n */\n"
+        )?);
+
+        Ok(())
+    }
+
+    fn occurrence(range: Vec<i32>, kind: SyntaxKind, symbol: &str) -> Occurrence {
+        Occurrence {
+            range,
+            syntax_kind: kind.into(),
+            symbol: symbol.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_locals() {
+        let code = "x = \"${foo}\" + bar.baz\n";
+        let highlights = vec![
+            occurrence(vec![0, 0, 1], SyntaxKind::Identifier, ""),
+            occurrence(vec![0, 4, 12], SyntaxKind::StringLiteral, ""),
+            occurrence(vec![0, 15, 18], SyntaxKind::Identifier, ""),
+            occurrence(vec![0, 19, 22], SyntaxKind::Identifier, ""),
+        ];
+        let locals = vec![
+            // Exact match
+            occurrence(vec![0, 0, 1], SyntaxKind::UnspecifiedSyntaxKind, "local 1"),
+            // Inside of the string
+            occurrence(vec![0, 7, 10], SyntaxKind::UnspecifiedSyntaxKind, "local 2"),
+            // Spans two highlights
+            occurrence(
+                vec![0, 15, 22],
+                SyntaxKind::UnspecifiedSyntaxKind,
+                "local 3",
+            ),
+        ];
+
+        let merged: Vec<_> = merge_locals(highlights, locals, code)
+            .into_iter()
+            .map(|occ| {
+                (
+                    occ.range,
+                    occ.syntax_kind.enum_value_or_default(),
+                    occ.symbol,
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            merged,
+            vec![
+                (vec![0, 0, 1], SyntaxKind::Identifier, "local 1".to_string()),
+                (vec![0, 4, 7], SyntaxKind::StringLiteral, "".to_string()),
+                (
+                    vec![0, 7, 10],
+                    SyntaxKind::StringLiteral,
+                    "local 2".to_string()
+                ),
+                (vec![0, 10, 12], SyntaxKind::StringLiteral, "".to_string()),
+                (
+                    vec![0, 15, 22],
+                    SyntaxKind::UnspecifiedSyntaxKind,
+                    "local 3".to_string()
+                ),
+                (vec![0, 15, 18], SyntaxKind::Identifier, "".to_string()),
+                (vec![0, 19, 22], SyntaxKind::Identifier, "".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_locals_converts_byte_columns() {
+        let code = "ü := x\n";
+        let highlights = vec![occurrence(vec![0, 0, 1], SyntaxKind::Identifier, "")];
+        // `ü` is two bytes long
+        let locals = vec![occurrence(
+            vec![0, 0, 2],
+            SyntaxKind::UnspecifiedSyntaxKind,
+            "local 1",
+        )];
+
+        let merged = merge_locals(highlights, locals, code);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].range, vec![0, 0, 1]);
+        assert_eq!(merged[0].symbol, "local 1");
+    }
+
+    // Every local definition and reference must survive merging into the highlights
+    #[test]
+    fn test_no_dropped_locals_in_snapshot_corpus() -> Result<(), std::io::Error> {
+        let crate_root: std::path::PathBuf = std::env::var("CARGO_MANIFEST_DIR").unwrap().into();
+        let snapshots = crate_root.join("src").join("snapshots");
+
+        let mut total = 0;
+        let mut dropped = vec![];
+        for dir in ["files", "files-with-locals"] {
+            for entry in read_dir(snapshots.join(dir))? {
+                let filepath = entry?.path();
+                let mut contents = String::new();
+                File::open(&filepath)?.read_to_string(&mut contents)?;
+
+                let filetype = &determine_filetype(&SourcegraphQuery {
+                    extension: filepath.extension().unwrap().to_str().unwrap().to_string(),
+                    filepath: filepath.to_str().unwrap().to_string(),
+                    filetype: None,
+                    line_length_limit: None,
+                    code: contents.clone(),
+                    ..Default::default()
+                });
+
+                let locals = match BundledParser::get_parser(filetype)
+                    .and_then(|parser| scip_syntax::get_locals(parser, contents.as_bytes()))
+                {
+                    Some(locals) => locals.expect("to compute locals"),
+                    None => continue,
+                };
+
+                let document =
+                    index_language(filetype, &contents, true, false).expect("to index file");
+                let lines: Vec<&str> = contents.split('\n').collect();
+                for local in locals {
+                    total += 1;
+
+                    let range = PackedRange::from_vec(&local.range).expect("valid range");
+                    let range = to_char_columns(range, &lines).to_vec();
+                    let found = document
+                        .occurrences
+                        .iter()
+                        .any(|occ| occ.range == range && occ.symbol == local.symbol);
+                    if !found {
+                        dropped.push(format!("{}: {:?}", filepath.display(), range));
+                    }
+                }
+            }
+        }
+
+        assert!(total > 0, "corpus should contain locals");
+        assert_eq!(dropped, Vec::<String>::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_error_ratio() {
+        assert_eq!(parse_error_ratio("go", "package main\n"), Some(0.0));
+        assert!(parse_error_ratio("go", "package main\n\nfunc main() {").unwrap() > 0.0);
+        assert_eq!(parse_error_ratio("not-a-language", ""), None);
+    }
+
+    #[test]
+    fn test_index_with_tree_matches_index() {
+        let code = "package main\r\n\nfunc main() {\r\n\tx := 1\r\n\t_ = x\r\n}\r\n";
+        let tree = parse_tree("go", code).expect("to parse go");
+
+        assert_eq!(
+            index_language_with_tree("go", code, &tree, true, true).unwrap(),
+            index_language("go", code, true, true).unwrap()
+        );
+    }
+
     #[test]
     fn test_all_files() -> Result<(), std::io::Error> {
         let crate_root: std::path::PathBuf = std::env::var("CARGO_MANIFEST_DIR").unwrap().into();
@@ -345,9 +966,10 @@ SELECT * FROM my_table
                 filetype: None,
                 line_length_limit: None,
                 code: contents.clone(),
+                ..Default::default()
             });
 
-            let indexed = index_language(filetype, &contents, true);
+            let indexed = index_language(filetype, &contents, true, false);
             if indexed.is_err() {
                 // assert failure
                 panic!("unknown filetype {:?}", filetype);
@@ -400,9 +1022,10 @@ SELECT * FROM my_table
                 filetype: None,
                 line_length_limit: None,
                 code: contents.clone(),
+                ..Default::default()
             });
 
-            let indexed = index_language(filetype, &contents, true);
+            let indexed = index_language(filetype, &contents, true, false);
             if indexed.is_err() {
                 // assert failure
                 panic!("unknown filetype {:?}", filetype);
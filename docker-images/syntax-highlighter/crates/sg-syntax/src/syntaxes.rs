@@ -0,0 +1,285 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use once_cell::sync::OnceCell;
+use syntect::parsing::{SyntaxDefinition, SyntaxSet, SyntaxSetBuilder};
+use walkdir::WalkDir;
+
+use crate::textmate::load_textmate_grammar;
+
+// One SyntaxSet shared by all worker threads. syntect compiles the regexes of a syntax
+// lazily on first use, so that work is also only done once per process.
+static SYNTAX_SET: OnceCell<SyntaxSet> = OnceCell::new();
+
+/// A way in which an extra syntax definition overlaps with one of the built-in syntaxes.
+///
+/// Extra syntaxes are added after the built-in ones, and syntect prefers the syntax that
+/// was added last, so in all of these cases the extra syntax wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxConflict {
+    Name {
+        syntax: String,
+    },
+    Scope {
+        syntax: String,
+        builtin: String,
+        scope: String,
+    },
+    Extension {
+        syntax: String,
+        builtin: String,
+        extension: String,
+    },
+}
+
+impl fmt::Display for SyntaxConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyntaxConflict::Name { syntax } => {
+                write!(f, "{syntax:?} replaces the built-in syntax with the same name")
+            }
+            SyntaxConflict::Scope {
+                syntax,
+                builtin,
+                scope,
+            } => write!(
+                f,
+                "{syntax:?} uses the scope {scope} of the built-in syntax {builtin:?}"
+            ),
+            SyntaxConflict::Extension {
+                syntax,
+                builtin,
+                extension,
+            } => write!(
+                f,
+                "{syntax:?} takes over the extension {extension:?} from the built-in syntax {builtin:?}"
+            ),
+        }
+    }
+}
+
+/// The built-in syntaxes merged with the syntaxes from [`load_extra_syntaxes`].
+pub struct ExtraSyntaxes {
+    pub syntax_set: SyntaxSet,
+
+    // Names of the syntaxes that were added
+    pub added: Vec<String>,
+
+    pub conflicts: Vec<SyntaxConflict>,
+
+    pub skipped: Vec<SkippedSyntax>,
+}
+
+/// A TextMate grammar that could not be converted, and why.
+pub type SkippedSyntax = (PathBuf, anyhow::Error);
+
+/// Merges extra syntax definitions into the built-in syntaxes.
+///
+/// `path` is either a directory, which is searched recursively for `.sublime-syntax`
+/// and `.tmLanguage` files, or a binary dump of a `SyntaxSet` (as written by
+/// `syntect::dumps::dump_to_file`). TextMate grammars are converted, see
+/// [`crate::textmate`].
+pub fn load_extra_syntaxes(path: &Path) -> Result<ExtraSyntaxes> {
+    let (definitions, skipped) = if path.is_dir() {
+        read_syntax_directory(path)?
+    } else {
+        (read_syntax_dump(path)?, vec![])
+    };
+
+    if definitions.is_empty() {
+        bail!("no syntax definitions found in {}", path.display());
+    }
+
+    let builtin = SyntaxSet::load_defaults_newlines();
+    let conflicts = find_conflicts(&builtin, &definitions);
+    let added = definitions.iter().map(|d| d.name.clone()).collect();
+
+    let mut builder = builtin.into_builder();
+    for definition in definitions {
+        builder.add(definition);
+    }
+
+    Ok(ExtraSyntaxes {
+        syntax_set: builder.build(),
+        added,
+        conflicts,
+        skipped,
+    })
+}
+
+fn read_syntax_directory(path: &Path) -> Result<(Vec<SyntaxDefinition>, Vec<SkippedSyntax>)> {
+    let mut builder = SyntaxSetBuilder::new();
+    builder
+        .add_from_folder(path, true)
+        .map_err(|err| anyhow!("could not load syntaxes from {}: {err}", path.display()))?;
+    let mut definitions = builder.syntaxes().to_vec();
+
+    // syntect only understands .sublime-syntax files, TextMate grammars are converted.
+    // One that can't be converted doesn't keep the others from loading.
+    let mut skipped = vec![];
+    for entry in WalkDir::new(path) {
+        let entry = entry.context("could not list syntax directory")?;
+        let is_textmate = entry
+            .path()
+            .extension()
+            .map_or(false, |ext| ext == "tmLanguage");
+        if !is_textmate {
+            continue;
+        }
+
+        match load_textmate_grammar(entry.path()) {
+            Ok(definition) => definitions.push(definition),
+            Err(err) => skipped.push((entry.into_path(), err)),
+        }
+    }
+
+    Ok((definitions, skipped))
+}
+
+fn read_syntax_dump(path: &Path) -> Result<Vec<SyntaxDefinition>> {
+    let syntax_set: SyntaxSet = syntect::dumps::from_dump_file(path)
+        .map_err(|err| anyhow!("could not read syntax dump {}: {err}", path.display()))?;
+
+    Ok(syntax_set.into_builder().syntaxes().to_vec())
+}
+
+fn find_conflicts(builtin: &SyntaxSet, definitions: &[SyntaxDefinition]) -> Vec<SyntaxConflict> {
+    let mut conflicts = vec![];
+    for definition in definitions {
+        for existing in builtin.syntaxes() {
+            if existing.name == definition.name {
+                conflicts.push(SyntaxConflict::Name {
+                    syntax: definition.name.clone(),
+                });
+                // Replacing a syntax is deliberate, no need to also list its scope and extensions.
+                continue;
+            }
+
+            if existing.scope == definition.scope {
+                conflicts.push(SyntaxConflict::Scope {
+                    syntax: definition.name.clone(),
+                    builtin: existing.name.clone(),
+                    scope: definition.scope.build_string(),
+                });
+            }
+
+            for extension in &definition.file_extensions {
+                if existing.file_extensions.contains(extension) {
+                    conflicts.push(SyntaxConflict::Extension {
+                        syntax: definition.name.clone(),
+                        builtin: existing.name.clone(),
+                        extension: extension.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Replaces the built-in syntaxes used for highlighting. Must be called before the first
+/// request is highlighted.
+pub fn install_syntax_set(syntax_set: SyntaxSet) -> Result<()> {
+    SYNTAX_SET
+        .set(syntax_set)
+        .map_err(|_| anyhow!("syntax set has already been initialized"))
+}
+
+/// The syntaxes used for highlighting, which are the built-in syntaxes unless others were
+/// installed with [`install_syntax_set`].
+pub fn syntax_set() -> &'static SyntaxSet {
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+#[cfg(test)]
+mod test {
+    use syntect::parsing::{BasicScopeStackOp, ParseState, ScopeStack};
+
+    use super::*;
+
+    fn extra_syntaxes_dir() -> PathBuf {
+        let crate_root: PathBuf = std::env::var("CARGO_MANIFEST_DIR").unwrap().into();
+        crate_root.join("testdata").join("extra-syntaxes")
+    }
+
+    #[test]
+    fn test_load_extra_syntaxes_from_directory() {
+        let extra = load_extra_syntaxes(&extra_syntaxes_dir()).expect("to load syntaxes");
+
+        let mut added = extra.added.clone();
+        added.sort();
+        assert_eq!(added, vec!["Acme Config", "Go Templates (Acme)", "Legacy"]);
+
+        let acme = extra
+            .syntax_set
+            .find_syntax_by_extension("acme")
+            .expect("acme syntax");
+        assert_eq!(acme.name, "Acme Config");
+
+        assert!(extra.conflicts.contains(&SyntaxConflict::Extension {
+            syntax: "Go Templates (Acme)".to_string(),
+            builtin: "Go".to_string(),
+            extension: "go".to_string(),
+        }));
+        assert!(extra
+            .conflicts
+            .iter()
+            .all(|conflict| !matches!(conflict, SyntaxConflict::Name { .. })));
+
+        // Uses a while rule
+        assert_eq!(extra.skipped.len(), 1);
+        assert!(extra.skipped[0].0.ends_with("quoted.tmLanguage"));
+    }
+
+    #[test]
+    fn test_textmate_grammars_are_converted() {
+        let extra = load_extra_syntaxes(&extra_syntaxes_dir()).expect("to load syntaxes");
+        let legacy = extra
+            .syntax_set
+            .find_syntax_by_extension("legacy")
+            .expect("legacy syntax");
+
+        let mut state = ParseState::new(legacy);
+        let mut stack = ScopeStack::new();
+        let mut scopes = vec![];
+        for (_, op) in state.parse_line("begin \"a\\n\" -- done\n", &extra.syntax_set) {
+            stack.apply_with_hook(&op, |op, _| {
+                if let BasicScopeStackOp::Push(scope) = op {
+                    scopes.push(scope.build_string());
+                }
+            });
+        }
+
+        assert_eq!(
+            scopes,
+            vec![
+                "source.legacy",
+                "keyword.control.legacy",
+                "string.quoted.double.legacy",
+                "punctuation.definition.string.begin.legacy",
+                "constant.character.escape.legacy",
+                "comment.line.double-dash.legacy",
+                "punctuation.definition.comment.legacy",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_extra_syntaxes_requires_definitions() {
+        let crate_root: PathBuf = std::env::var("CARGO_MANIFEST_DIR").unwrap().into();
+        assert!(load_extra_syntaxes(&crate_root.join("does-not-exist.packdump")).is_err());
+    }
+
+    #[test]
+    fn test_syntax_set_is_shared_between_threads() {
+        let here = syntax_set() as *const SyntaxSet as usize;
+        let there = std::thread::spawn(|| syntax_set() as *const SyntaxSet as usize)
+            .join()
+            .unwrap();
+        assert_eq!(here, there);
+    }
+}
@@ -0,0 +1,307 @@
+//! Conversion of TextMate grammars (`.tmLanguage` files) to syntect syntax definitions.
+//!
+//! syntect only reads `.sublime-syntax` files, but their contexts can express what the
+//! rules of a TextMate grammar do, which is also how Sublime Text loads them:
+//!
+//! - `match` rules become match patterns, scoped with the `name` of the rule.
+//! - `begin`/`end` rules push an anonymous context that pops on `end`. The `name` of
+//!   the rule is its `meta_scope`, which also covers the begin and end matches, and
+//!   `contentName` its `meta_content_scope`.
+//! - Every rule of the `repository` becomes a context. Includes refer to those, to
+//!   `main` for `$self` and `$base`, and to other syntaxes by their scope.
+//!
+//! `while` rules have no equivalent, grammars that use them are not converted.
+//! Patterns inside captures are dropped, the capture only keeps its scope.
+
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use syntect::parsing::SyntaxDefinition;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Grammar {
+    name: String,
+    scope_name: String,
+    #[serde(default)]
+    file_types: Vec<String>,
+    first_line_match: Option<String>,
+    #[serde(default)]
+    patterns: Vec<Rule>,
+    #[serde(default)]
+    repository: BTreeMap<String, Rule>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    include: Option<String>,
+    name: Option<String>,
+    content_name: Option<String>,
+    #[serde(rename = "match")]
+    match_: Option<String>,
+    begin: Option<String>,
+    end: Option<String>,
+    #[serde(rename = "while")]
+    while_: Option<String>,
+    #[serde(default)]
+    captures: BTreeMap<String, Capture>,
+    #[serde(default)]
+    begin_captures: BTreeMap<String, Capture>,
+    #[serde(default)]
+    end_captures: BTreeMap<String, Capture>,
+    #[serde(default)]
+    patterns: Vec<Rule>,
+    #[serde(default)]
+    repository: BTreeMap<String, Rule>,
+    // Booleans are <integer>1</integer> in most grammars, and <true/> in some
+    apply_end_pattern_last: Option<plist::Value>,
+    disabled: Option<plist::Value>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Capture {
+    name: Option<String>,
+}
+
+/// Reads a `.tmLanguage` file and converts it to a syntax definition.
+pub fn load_textmate_grammar(path: &Path) -> Result<SyntaxDefinition> {
+    let grammar: Grammar = plist::from_file(path)
+        .map_err(|err| anyhow!("could not read {}: {err}", path.display()))?;
+
+    let yaml = to_sublime_syntax(&grammar)?;
+    SyntaxDefinition::load_from_str(&yaml, true, None)
+        .map_err(|err| anyhow!("could not convert {}: {err}", path.display()))
+}
+
+fn to_sublime_syntax(grammar: &Grammar) -> Result<String> {
+    // Rules can have repositories of their own. They are rare, and merged into the one
+    // of the grammar, whose rules win when names are used twice.
+    let mut repository = BTreeMap::new();
+    collect_repository(&grammar.repository, &mut repository);
+    let converter = Converter { repository };
+
+    let mut contexts = Map::new();
+    contexts.insert(
+        "main".to_string(),
+        Value::Array(converter.rules(&grammar.patterns)?),
+    );
+    for (&name, rule) in &converter.repository {
+        contexts.insert(context_name(name), Value::Array(converter.rule(rule)?));
+    }
+
+    let mut syntax = json!({
+        "name": grammar.name,
+        "scope": grammar.scope_name,
+        "file_extensions": grammar.file_types,
+        "contexts": contexts,
+    });
+    if let Some(first_line_match) = &grammar.first_line_match {
+        syntax["first_line_match"] = json!(first_line_match);
+    }
+
+    let mut yaml = String::new();
+    write_yaml(&syntax, &mut yaml);
+    Ok(yaml)
+}
+
+fn collect_repository<'a>(
+    rules: &'a BTreeMap<String, Rule>,
+    repository: &mut BTreeMap<&'a str, &'a Rule>,
+) {
+    for (name, rule) in rules {
+        repository.entry(name.as_str()).or_insert(rule);
+    }
+    for rule in rules.values() {
+        collect_nested_repositories(rule, repository);
+    }
+}
+
+fn collect_nested_repositories<'a>(rule: &'a Rule, repository: &mut BTreeMap<&'a str, &'a Rule>) {
+    collect_repository(&rule.repository, repository);
+    for rule in &rule.patterns {
+        collect_nested_repositories(rule, repository);
+    }
+}
+
+// Prefixed, so that repository rules can't replace `main` or `prototype`
+fn context_name(name: &str) -> String {
+    format!("repository.{name}")
+}
+
+struct Converter<'a> {
+    repository: BTreeMap<&'a str, &'a Rule>,
+}
+
+impl Converter<'_> {
+    fn rules(&self, rules: &[Rule]) -> Result<Vec<Value>> {
+        let mut patterns = vec![];
+        for rule in rules {
+            patterns.extend(self.rule(rule)?);
+        }
+        Ok(patterns)
+    }
+
+    fn rule(&self, rule: &Rule) -> Result<Vec<Value>> {
+        if is_set(&rule.disabled) {
+            return Ok(vec![]);
+        }
+
+        if let Some(include) = &rule.include {
+            return Ok(self.include(include).into_iter().collect());
+        }
+
+        if let Some(regex) = &rule.while_ {
+            bail!("while rules are not supported, found {regex:?}");
+        }
+
+        if let Some(regex) = &rule.match_ {
+            let mut pattern = json!({ "match": regex });
+            if let Some(name) = &rule.name {
+                pattern["scope"] = json!(name);
+            }
+            add_captures(&mut pattern, &rule.captures);
+            return Ok(vec![pattern]);
+        }
+
+        let Some(begin) = &rule.begin else {
+            // Only groups other rules
+            return self.rules(&rule.patterns);
+        };
+        let end = rule
+            .end
+            .as_ref()
+            .ok_or_else(|| anyhow!("rule {begin:?} has a begin but no end"))?;
+
+        let mut context = vec![];
+        if let Some(name) = &rule.name {
+            context.push(json!({ "meta_scope": name }));
+        }
+        if let Some(content_name) = &rule.content_name {
+            context.push(json!({ "meta_content_scope": content_name }));
+        }
+
+        let mut end_pattern = json!({ "match": end, "pop": true });
+        add_captures(
+            &mut end_pattern,
+            or_captures(&rule.end_captures, &rule.captures),
+        );
+        let patterns = self.rules(&rule.patterns)?;
+        if is_set(&rule.apply_end_pattern_last) {
+            context.extend(patterns);
+            context.push(end_pattern);
+        } else {
+            context.push(end_pattern);
+            context.extend(patterns);
+        }
+
+        let mut pattern = json!({ "match": begin, "push": context });
+        add_captures(
+            &mut pattern,
+            or_captures(&rule.begin_captures, &rule.captures),
+        );
+        Ok(vec![pattern])
+    }
+
+    // Includes of repository rules that don't exist are dropped, like TextMate does
+    fn include(&self, include: &str) -> Option<Value> {
+        let context = match include {
+            "$self" | "$base" => "main".to_string(),
+            _ => match include.split_once('#') {
+                Some(("", name)) => {
+                    self.repository.get(name)?;
+                    context_name(name)
+                }
+                Some((scope, name)) => format!("scope:{scope}#{}", context_name(name)),
+                None => format!("scope:{include}"),
+            },
+        };
+
+        Some(json!({ "include": context }))
+    }
+}
+
+fn or_captures<'a>(
+    captures: &'a BTreeMap<String, Capture>,
+    fallback: &'a BTreeMap<String, Capture>,
+) -> &'a BTreeMap<String, Capture> {
+    if captures.is_empty() {
+        fallback
+    } else {
+        captures
+    }
+}
+
+fn add_captures(pattern: &mut Value, captures: &BTreeMap<String, Capture>) {
+    let scopes: Map<String, Value> = captures
+        .iter()
+        .filter_map(|(group, capture)| Some((group.clone(), json!(capture.name.as_ref()?))))
+        .collect();
+
+    if !scopes.is_empty() {
+        pattern["captures"] = Value::Object(scopes);
+    }
+}
+
+fn is_set(value: &Option<plist::Value>) -> bool {
+    match value {
+        Some(plist::Value::Boolean(value)) => *value,
+        Some(plist::Value::Integer(value)) => value.as_signed() != Some(0),
+        _ => false,
+    }
+}
+
+// sublime-syntax files are YAML, and the capture groups of a pattern have to be integer
+// keys, which JSON doesn't have. Everything is written as YAML flow collections, with
+// JSON strings, which are valid double-quoted YAML strings.
+fn write_yaml(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            out.push('{');
+            for (idx, (key, value)) in map.iter().enumerate() {
+                if idx > 0 {
+                    out.push_str(", ");
+                }
+                if !key.is_empty() && key.bytes().all(|b| b.is_ascii_digit()) {
+                    out.push_str(key);
+                } else {
+                    out.push_str(&json!(key).to_string());
+                }
+                out.push_str(": ");
+                write_yaml(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (idx, item) in items.iter().enumerate() {
+                if idx > 0 {
+                    out.push_str(", ");
+                }
+                write_yaml(item, out);
+            }
+            out.push(']');
+        }
+        value => out.push_str(&value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_capture_groups_are_integer_keys() {
+        let mut yaml = String::new();
+        write_yaml(
+            &json!({ "captures": { "1": "x" }, "match": "(a)\\\"", "pop": true }),
+            &mut yaml,
+        );
+        assert_eq!(
+            yaml,
+            r#"{"captures": {1: "x"}, "match": "(a)\\\"", "pop": true}"#
+        );
+    }
+}
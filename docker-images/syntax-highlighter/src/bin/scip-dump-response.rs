@@ -14,6 +14,10 @@ struct Arguments {
     /// Include locals, default false
     #[arg(long)]
     include_locals: bool,
+
+    /// Include syntax error diagnostics, default false
+    #[arg(long)]
+    include_diagnostics: bool,
 }
 
 fn main() -> Result<(), std::io::Error> {
@@ -47,12 +51,18 @@ fn main() -> Result<(), std::io::Error> {
         filepath: "".to_string(),
         filetype: None,
         line_length_limit: None,
+        ..Default::default()
     });
 
     println!("  filetype: {:?}", filetype);
 
-    let document = sg_syntax::treesitter_index(&filetype, &contents, args.include_locals)
-        .expect("parse document");
+    let document = sg_syntax::treesitter_index(
+        &filetype,
+        &contents,
+        args.include_locals,
+        args.include_diagnostics,
+    )
+    .expect("parse document");
     println!("  parsed document");
 
     scip::write_message_to_file(output, document).expect("writes document");
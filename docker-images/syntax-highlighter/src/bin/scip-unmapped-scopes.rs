@@ -0,0 +1,98 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use clap::Parser;
+use sg_syntax::{
+    determine_language,
+    scope_mapping::{install_scope_mapping, ScopeMapping},
+    DocumentGenerator, SourcegraphQuery,
+};
+use syntect::parsing::SyntaxSet;
+use walkdir::WalkDir;
+
+/// Reports the syntect scopes found in a corpus that the scope mapping neither
+/// maps to a SyntaxKind nor ignores.
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Arguments {
+    /// Root directory of the corpus to highlight
+    root_dir: String,
+
+    /// Scope mapping to validate, defaults to the embedded mapping
+    #[arg(long)]
+    mapping: Option<String>,
+
+    /// Number of example files to print for every unmapped scope
+    #[arg(long, default_value_t = 1)]
+    examples: usize,
+}
+
+#[derive(Default)]
+struct UnmappedScope {
+    count: usize,
+    examples: Vec<String>,
+}
+
+fn main() {
+    let args = Arguments::parse();
+
+    if let Some(mapping) = &args.mapping {
+        let mapping = ScopeMapping::from_path(Path::new(mapping)).expect("valid scope mapping");
+        install_scope_mapping(mapping).expect("scope mapping to be installed once");
+    }
+
+    let syntax_set = SyntaxSet::load_defaults_newlines();
+
+    let mut files = 0;
+    let mut unmapped: HashMap<String, UnmappedScope> = HashMap::new();
+    for entry in WalkDir::new(&args.root_dir) {
+        let entry = entry.expect("valid directory entry");
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        // Skip binary or otherwise non-UTF-8 files
+        let contents = match fs::read_to_string(entry.path()) {
+            Ok(contents) => contents,
+            Err(_) => continue,
+        };
+
+        let filepath = entry.path().display().to_string();
+        let q = SourcegraphQuery {
+            filepath: filepath.clone(),
+            code: contents,
+            ..Default::default()
+        };
+
+        let syntax_def = match determine_language(&q, &syntax_set) {
+            Ok(syntax_def) => syntax_def,
+            Err(_) => continue,
+        };
+
+        let (_, scopes) = DocumentGenerator::new(&syntax_set, syntax_def, &q.code, None)
+            .generate_with_unhandled_scopes();
+
+        files += 1;
+        for scope in scopes {
+            let unmapped_scope = unmapped.entry(scope.build_string()).or_default();
+            unmapped_scope.count += 1;
+            if unmapped_scope.examples.len() < args.examples {
+                unmapped_scope.examples.push(filepath.clone());
+            }
+        }
+    }
+
+    let mut unmapped = unmapped.into_iter().collect::<Vec<_>>();
+    unmapped.sort_by(|(a_scope, a), (b_scope, b)| b.count.cmp(&a.count).then(a_scope.cmp(b_scope)));
+
+    println!(
+        "{} unmapped scopes in {} highlighted files",
+        unmapped.len(),
+        files
+    );
+    for (scope, info) in unmapped {
+        println!("{:>6} {}", info.count, scope);
+        for example in info.examples {
+            println!("         {}", example);
+        }
+    }
+}
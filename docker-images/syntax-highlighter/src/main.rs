@@ -7,9 +7,22 @@ use std::path;
 
 use protobuf::Message;
 use rocket::serde::json::{json, Json, Value as JsonValue};
-use scip_treesitter_languages::parsers::BundledParser;
+// `::` because the /scip route is also named scip
+use ::scip::types::Document;
+use scip_syntax::languages::{get_local_configuration, get_tag_configuration};
+use scip_treesitter_languages::{
+    highlights::get_highlighting_configuration,
+    parsers::BundledParser,
+    plugins::{install_plugins, load_plugins},
+};
 use serde::Deserialize;
-use sg_syntax::{ScipHighlightQuery, SourcegraphQuery};
+use sg_syntax::{
+    classify::{classify, install_skip_policy, skip_policy, SkipPolicy},
+    notebook::Notebook,
+    scope_mapping::{install_scope_mapping, ScopeMapping},
+    syntaxes::{install_syntax_set, load_extra_syntaxes},
+    ScipHighlightQuery, SourcegraphQuery,
+};
 
 #[post("/", format = "application/json", data = "<q>")]
 fn syntect(q: Json<SourcegraphQuery>) -> JsonValue {
@@ -55,11 +68,33 @@ pub fn jsonify_err(e: impl ToString) -> JsonValue {
 
 #[post("/symbols", format = "application/json", data = "<q>")]
 fn symbols(q: Json<SymbolQuery>) -> JsonValue {
+    let flags = classify(&q.filename, &q.content);
+    let mut response = if skip_policy().should_skip(&flags) {
+        match Document::new().write_to_bytes() {
+            Ok(encoded) => json!({"scip": base64::encode(encoded), "plaintext": true}),
+            Err(err) => return jsonify_err(err),
+        }
+    } else {
+        file_symbols(&q)
+    };
+
+    // Errors are returned without flags, like for the highlight endpoints
+    if response.get("error").is_none() {
+        response["flags"] = json!(flags);
+    }
+    response
+}
+
+fn file_symbols(q: &SymbolQuery) -> JsonValue {
+    if sg_syntax::notebook::is_notebook(&q.filename) {
+        return notebook_symbols(&q.content);
+    }
+
     let path = path::Path::new(&q.filename);
-    let extension = match match path.extension() {
+    let file_name = match match path.file_name() {
         Some(vals) => vals,
         None => {
-            return json!({"error": "Extensionless file"});
+            return json!({"error": "Missing file name"});
         }
     }
     .to_str()
@@ -69,9 +104,11 @@ fn symbols(q: Json<SymbolQuery>) -> JsonValue {
             return json!({"error": "Invalid codepoint"});
         }
     };
-    let parser = match BundledParser::get_parser_from_extension(extension) {
+
+    // Also finds files without an extension, e.g. Dockerfile and Makefile
+    let parser = match BundledParser::get_parser_from_file_name(file_name) {
         Some(parser) => parser,
-        None => return json!({"error": "Could not infer parser from extension"}),
+        None => return json!({"error": "Could not infer parser from file name"}),
     };
 
     let document = match scip_syntax::get_symbols(parser, q.content.as_bytes()) {
@@ -91,6 +128,55 @@ fn symbols(q: Json<SymbolQuery>) -> JsonValue {
     json!({"scip": base64::encode(encoded), "plaintext": false})
 }
 
+// Symbols of all code cells, with ranges in the virtual document described by `notebook`.
+fn notebook_symbols(content: &str) -> JsonValue {
+    let notebook = match Notebook::parse(content) {
+        Ok(notebook) => notebook,
+        Err(err) => return jsonify_err(err),
+    };
+    let layout = notebook.layout();
+
+    let document = match notebook.symbols(&layout) {
+        Ok(document) => document,
+        Err(err) => return jsonify_err(err),
+    };
+
+    let encoded = match document.write_to_bytes() {
+        Ok(vals) => vals,
+        Err(err) => {
+            return jsonify_err(err);
+        }
+    };
+
+    json!({"scip": base64::encode(encoded), "plaintext": false, "notebook": layout})
+}
+
+#[get("/semantic-tokens/legend")]
+fn semantic_tokens_legend() -> JsonValue {
+    json!(sg_syntax::semantic_tokens::legend())
+}
+
+// The tree-sitter languages of this build: bundled grammars that were compiled in (see
+// the features of scip-treesitter-languages) and loaded plugins.
+#[get("/languages")]
+fn languages() -> JsonValue {
+    let languages: Vec<_> = BundledParser::available()
+        .into_iter()
+        .map(|parser| {
+            let name = parser.get_language_name();
+            json!({
+                "name": name,
+                "plugin": matches!(parser, BundledParser::Plugin(_)),
+                "highlights": get_highlighting_configuration(name).is_some(),
+                "symbols": get_tag_configuration(parser).is_some(),
+                "locals": get_local_configuration(parser).is_some(),
+            })
+        })
+        .collect();
+
+    json!({ "languages": languages })
+}
+
 #[get("/health")]
 fn health() -> &'static str {
     "OK"
@@ -116,6 +202,82 @@ fn rocket() -> _ {
         _ => {}
     };
 
+    // Replace the embedded syntect scope mapping if one was provided. This has to happen
+    // before the first request is highlighted.
+    if let Ok(path) = std::env::var("SCOPE_MAPPING_FILE") {
+        match ScopeMapping::from_path(path::Path::new(&path)) {
+            Ok(mapping) => install_scope_mapping(mapping)
+                .expect("scope mapping is installed before the first request"),
+            Err(err) => {
+                eprintln!("Failed to load scope mapping: {:#}", err);
+                std::process::exit(1)
+            }
+        }
+    }
+
+    // Merge additional syntect syntaxes (e.g. for proprietary languages) into the
+    // built-in ones. Like the scope mapping, this has to happen before the first request.
+    if let Ok(path) = std::env::var("EXTRA_SYNTAXES_PATH") {
+        match load_extra_syntaxes(path::Path::new(&path)) {
+            Ok(extra) => {
+                eprintln!("Loaded extra syntaxes: {}", extra.added.join(", "));
+                for conflict in &extra.conflicts {
+                    eprintln!("WARNING: extra syntax conflict: {}", conflict);
+                }
+                for (path, err) in &extra.skipped {
+                    eprintln!("WARNING: skipping {}: {:#}", path.display(), err);
+                }
+
+                install_syntax_set(extra.syntax_set)
+                    .expect("syntax set is installed before the first request");
+            }
+            Err(err) => {
+                eprintln!("Failed to load extra syntaxes: {:#}", err);
+                std::process::exit(1)
+            }
+        }
+    }
+
+    // Content that isn't highlighted at all, e.g. SKIP_HIGHLIGHTING=minified,binary
+    if let Ok(spec) = std::env::var("SKIP_HIGHLIGHTING") {
+        match SkipPolicy::parse(&spec) {
+            Ok(policy) => install_skip_policy(policy)
+                .expect("skip policy is installed before the first request"),
+            Err(err) => {
+                eprintln!("Failed to parse SKIP_HIGHLIGHTING: {:#}", err);
+                std::process::exit(1)
+            }
+        }
+    }
+
+    // Load tree-sitter grammars that aren't bundled from shared libraries. A broken plugin
+    // is reported, but doesn't keep the other languages from being served.
+    if let Ok(path) = std::env::var("GRAMMAR_PLUGINS_PATH") {
+        match load_plugins(path::Path::new(&path)) {
+            Ok(loaded) => {
+                for error in &loaded.errors {
+                    eprintln!(
+                        "WARNING: skipping grammar plugin {}: {:#}",
+                        error.path.display(),
+                        error.error
+                    );
+                }
+                let names: Vec<_> = loaded.plugins.iter().map(|p| p.name).collect();
+                eprintln!("Loaded grammar plugins: {}", names.join(", "));
+
+                install_plugins(loaded.plugins)
+                    .expect("plugins are installed before the first request");
+            }
+            Err(err) => {
+                eprintln!("Failed to load grammar plugins: {:#}", err);
+                std::process::exit(1)
+            }
+        }
+    }
+
+    // Load the syntect syntaxes, which are shared by all workers, on-startup as well.
+    sg_syntax::syntaxes::syntax_set();
+
     // load configurations on-startup instead of on-first-request.
     // TODO: load individual languages lazily on-request instead, currently
     // CONFIGURATIONS.get will load every configured configuration together.
@@ -129,6 +291,17 @@ fn rocket() -> _ {
     };
 
     rocket::build()
-        .mount("/", routes![syntect, lsif, scip, symbols, health])
+        .mount(
+            "/",
+            routes![
+                syntect,
+                lsif,
+                scip,
+                symbols,
+                semantic_tokens_legend,
+                languages,
+                health
+            ],
+        )
         .register("/", catchers![not_found])
 }
@@ -61,31 +61,70 @@ var supportedLanguages = map[string]struct{}{
 	// TODO: Will support these after 5.1 release
 	// "c":          {},
 	// "cpp":        {},
-	"c_sharp":    {},
-	"go":         {},
-	"java":       {},
-	"javascript": {},
-	"kotlin":     {},
-	"python":     {},
-	"ruby":       {},
-	"rust":       {},
-	"scala":      {},
-	"typescript": {},
-	"zig":        {},
+	"c_sharp":     {},
+	"clojure":     {},
+	"cmake":       {},
+	"css":         {},
+	"dart":        {},
+	"elixir":      {},
+	"erlang":      {},
+	"go":          {},
+	"graphql":     {},
+	"haskell":     {},
+	"hcl":         {},
+	"html":        {},
+	"java":        {},
+	"javascript":  {},
+	"json":        {},
+	"kotlin":      {},
+	"lua":         {},
+	"makefile":    {},
+	"objc":        {},
+	"objective-c": {},
+	"ocaml":       {},
+	"protobuf":    {},
+	"python":      {},
+	"ruby":        {},
+	"rust":        {},
+	"scala":       {},
+	"scss":        {},
+	"shell":       {},
+	"starlark":    {},
+	"svelte":      {},
+	"swift":       {},
+	"thrift":      {},
+	"toml":        {},
+	"typescript":  {},
+	"vue":         {},
+	"yaml":        {},
+	"zig":         {},
 }
 
 var DefaultEngines = map[string]ParserType{
 	// Add the languages we want to turn on by default (you'll need to
 	// update the ctags_config module for supported languages as well)
 	"c_sharp":    ScipCtags,
+	"css":        ScipCtags,
 	"go":         ScipCtags,
+	"graphql":    ScipCtags,
+	"hcl":        ScipCtags,
+	"html":       ScipCtags,
 	"javascript": ScipCtags,
+	"json":       ScipCtags,
 	"kotlin":     ScipCtags,
+	"protobuf":   ScipCtags,
 	"python":     ScipCtags,
 	"ruby":       ScipCtags,
 	"rust":       ScipCtags,
 	"scala":      ScipCtags,
+	"scss":       ScipCtags,
+	"starlark":   ScipCtags,
+	"svelte":     ScipCtags,
+	"thrift":     ScipCtags,
+	"toml":       ScipCtags,
 	"typescript": ScipCtags,
+	"vue":        ScipCtags,
+	"yaml":       ScipCtags,
 	"zig":        ScipCtags,
 
 	// TODO: Not ready to turn on the following yet. Worried about not handling enough cases.
@@ -39,6 +39,9 @@ const (
 	SyntaxEngineSyntect    = "syntect"
 	SyntaxEngineTreesitter = "tree-sitter"
 	SyntaxEngineScipSyntax = "scip-syntax"
+	// SyntaxEngineAuto uses scip-syntax when tree-sitter parses the file without
+	// too many errors, and syntect otherwise. Only supported by the /scip endpoint.
+	SyntaxEngineAuto = "auto"
 
 	SyntaxEngineInvalid = "invalid"
 )
@@ -87,6 +90,14 @@ type Query struct {
 	// Code is the literal code to highlight.
 	Code string `json:"code"`
 
+	// CodeBase64 is the base64 encoded content of files that are not UTF-8.
+	// It replaces Code when set.
+	CodeBase64 string `json:"code_base64,omitempty"`
+
+	// Charset is the encoding of CodeBase64, e.g. "Shift_JIS". The server
+	// detects the encoding when it is empty.
+	Charset string `json:"charset,omitempty"`
+
 	// LineLengthLimit is the maximum length of line that will be highlighted if set.
 	// Defaults to no max if zero.
 	// If CSS is false, LineLengthLimit is ignored.
@@ -102,6 +113,27 @@ type Query struct {
 
 	// Which highlighting engine to use
 	Engine string `json:"engine"`
+
+	// IncludeDiagnostics attaches tree-sitter syntax errors to the SCIP
+	// document as diagnostics. Ignored by the syntect engine.
+	IncludeDiagnostics bool `json:"include_diagnostics,omitempty"`
+}
+
+// ContentFlags describes what kind of content a file contains.
+//
+// NOTE: Keep in sync: docker-images/syntax-highlighter/crates/sg-syntax/src/classify.rs
+type ContentFlags struct {
+	// Minified files have very long lines with little whitespace.
+	Minified bool `json:"minified"`
+
+	// Generated files have a well-known generated code marker.
+	Generated bool `json:"generated"`
+
+	// Binary files contain NUL bytes or mostly control characters.
+	Binary bool `json:"binary"`
+
+	// Lockfile is set for package manager lock files.
+	Lockfile bool `json:"lockfile"`
 }
 
 // Response represents a response to a code highlighting query.
@@ -112,6 +144,10 @@ type Response struct {
 	// Plaintext indicates whether or not a syntax could not be found for the
 	// file and instead it was rendered as plain text.
 	Plaintext bool
+
+	// Flags describes the content of the file. Files can be returned as plain
+	// text because of their flags, depending on the server configuration.
+	Flags ContentFlags
 }
 
 var (
@@ -137,6 +173,10 @@ type response struct {
 	// Used by the /scip endpoint
 	Scip      string `json:"scip"`
 	Plaintext bool   `json:"plaintext"`
+	// Engine that produced the /scip response, which can differ from the
+	// requested engine when using SyntaxEngineAuto.
+	Engine string       `json:"engine"`
+	Flags  ContentFlags `json:"flags"`
 
 	// Error response fields.
 	Error string `json:"error"`
@@ -228,6 +268,7 @@ func (c *Client) Highlight(ctx context.Context, q *Query, format HighlightRespon
 	response := &Response{
 		Data:      r.Data,
 		Plaintext: r.Plaintext,
+		Flags:     r.Flags,
 	}
 
 	// If SCIP is set, prefer it over HTML
@@ -262,8 +303,9 @@ type SymbolsQuery struct {
 
 // SymbolsResponse represents a response to a symbols query.
 type SymbolsResponse struct {
-	Scip      string `json:"scip"`
-	Plaintext bool   `json:"plaintext"`
+	Scip      string       `json:"scip"`
+	Plaintext bool         `json:"plaintext"`
+	Flags     ContentFlags `json:"flags"`
 }
 
 func (c *Client) Symbols(ctx context.Context, q *SymbolsQuery) (*SymbolsResponse, error) {
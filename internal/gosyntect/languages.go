@@ -1,25 +1,54 @@
 package gosyntect
 
 var treesitterSupportedFiletypes = map[string]struct{}{
-	"c":          {},
-	"c++":        {},
-	"c_sharp":    {},
-	"cpp":        {},
-	"go":         {},
-	"java":       {},
-	"javascript": {},
-	"jsonnet":    {},
-	"jsx":        {},
-	"kotlin":     {},
-	"matlab":     {},
-	"nickel":     {},
-	"perl":       {},
-	"python":     {},
-	"ruby":       {},
-	"rust":       {},
-	"scala":      {},
-	"tsx":        {},
-	"typescript": {},
-	"xlsg":       {},
-	"zig":        {},
+	"bash":        {},
+	"c":           {},
+	"c++":         {},
+	"c_sharp":     {},
+	"clojure":     {},
+	"cmake":       {},
+	"cpp":         {},
+	"css":         {},
+	"dart":        {},
+	"dockerfile":  {},
+	"elixir":      {},
+	"erlang":      {},
+	"go":          {},
+	"graphql":     {},
+	"haskell":     {},
+	"hcl":         {},
+	"html":        {},
+	"java":        {},
+	"javascript":  {},
+	"json":        {},
+	"jsonnet":     {},
+	"jsx":         {},
+	"kotlin":      {},
+	"lua":         {},
+	"make":        {},
+	"makefile":    {},
+	"matlab":      {},
+	"nickel":      {},
+	"objc":        {},
+	"objective-c": {},
+	"ocaml":       {},
+	"perl":        {},
+	"protobuf":    {},
+	"python":      {},
+	"ruby":        {},
+	"rust":        {},
+	"scala":       {},
+	"scss":        {},
+	"shell":       {},
+	"starlark":    {},
+	"svelte":      {},
+	"swift":       {},
+	"thrift":      {},
+	"toml":        {},
+	"tsx":         {},
+	"typescript":  {},
+	"vue":         {},
+	"xlsg":        {},
+	"yaml":        {},
+	"zig":         {},
 }
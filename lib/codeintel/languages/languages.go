@@ -10,7 +10,8 @@ import (
 
 // Make sure all names are lowercase here, since they are normalized
 var enryLanguageMappings = map[string]string{
-	"c#": "c_sharp",
+	"c#":              "c_sharp",
+	"protocol buffer": "protobuf",
 }
 
 func NormalizeLanguage(filetype string) string {